@@ -2,12 +2,17 @@
 
 pub mod window_commands;
 
+use std::collections::{BTreeMap, HashMap};
+
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
 use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::log_level::LogLevel;
 use crate::domain::log_watching::log_source::{LogSource, LogSourceStatus};
-use crate::domain::parsing::LaravelDailyLogDetector;
+use crate::domain::log_watching::value_objects::source_timezone::SourceTimezone;
+use crate::domain::log_watching::value_objects::text_encoding::TextEncoding;
+use crate::domain::parsing::{CustomParserDefinition, LaravelDailyLogDetector, PatternSyntax};
 
 use super::state::SharedLogWatcherState;
 
@@ -153,6 +158,154 @@ pub fn update_source_status(
     state_guard.update_status(&source_id, status, None)
 }
 
+/// Get how long a half-written line waits for its newline, in milliseconds.
+#[tauri::command]
+pub fn get_partial_line_timeout(state: State<SharedLogWatcherState>) -> u64 {
+    let state_guard = state.lock().unwrap();
+    state_guard.get_partial_line_timeout()
+}
+
+/// Set how long a half-written line waits for its newline, in milliseconds.
+#[tauri::command]
+pub fn set_partial_line_timeout(
+    state: State<SharedLogWatcherState>,
+    timeout_ms: u64,
+) -> Result<(), String> {
+    let mut state_guard = state.lock().unwrap();
+    state_guard.set_partial_line_timeout(timeout_ms)
+}
+
+/// Register or replace a user-defined regex parser.
+#[tauri::command]
+pub fn register_custom_parser(
+    state: State<SharedLogWatcherState>,
+    name: String,
+    pattern: String,
+    syntax: Option<PatternSyntax>,
+    timestamp_format: Option<String>,
+    continuation_pattern: Option<String>,
+) -> Result<(), String> {
+    let mut state_guard = state.lock().unwrap();
+    state_guard.register_custom_parser(CustomParserDefinition {
+        name,
+        pattern,
+        syntax: syntax.unwrap_or_default(),
+        timestamp_format,
+        continuation_pattern,
+    })
+}
+
+/// Remove a user-defined regex parser.
+#[tauri::command]
+pub fn remove_custom_parser(
+    state: State<SharedLogWatcherState>,
+    name: String,
+) -> Result<(), String> {
+    let mut state_guard = state.lock().unwrap();
+    state_guard.remove_custom_parser(&name)
+}
+
+/// Get all user-defined regex parsers.
+#[tauri::command]
+pub fn get_custom_parsers(state: State<SharedLogWatcherState>) -> Vec<CustomParserDefinition> {
+    let state_guard = state.lock().unwrap();
+    state_guard.get_custom_parsers()
+}
+
+/// Register or replace a user-defined grok pattern.
+#[tauri::command]
+pub fn register_grok_pattern(
+    state: State<SharedLogWatcherState>,
+    name: String,
+    definition: String,
+) -> Result<(), String> {
+    let mut state_guard = state.lock().unwrap();
+    state_guard.register_grok_pattern(&name, &definition)
+}
+
+/// Remove a user-defined grok pattern.
+#[tauri::command]
+pub fn remove_grok_pattern(
+    state: State<SharedLogWatcherState>,
+    name: String,
+) -> Result<(), String> {
+    let mut state_guard = state.lock().unwrap();
+    state_guard.remove_grok_pattern(&name)
+}
+
+/// Get all user-defined grok patterns.
+#[tauri::command]
+pub fn get_grok_patterns(state: State<SharedLogWatcherState>) -> BTreeMap<String, String> {
+    let state_guard = state.lock().unwrap();
+    state_guard.get_grok_patterns()
+}
+
+/// Get the names of all built-in and custom parsers.
+#[tauri::command]
+pub fn get_parser_names(state: State<SharedLogWatcherState>) -> Vec<String> {
+    let state_guard = state.lock().unwrap();
+    state_guard.get_parser_names()
+}
+
+/// Assign a parser to a source, overriding format detection (None re-detects).
+#[tauri::command]
+pub fn set_source_parser(
+    state: State<SharedLogWatcherState>,
+    source_id: String,
+    parser: Option<String>,
+) -> Result<LogSource, String> {
+    let mut state_guard = state.lock().unwrap();
+    state_guard.set_source_parser(&source_id, parser)
+}
+
+/// Set the timezone a source writes naive timestamps in, e.g. "Europe/Oslo",
+/// "+02:00" or "local", and re-parse its buffered entries.
+#[tauri::command]
+pub fn set_source_timezone(
+    state: State<SharedLogWatcherState>,
+    source_id: String,
+    timezone: String,
+) -> Result<LogSource, String> {
+    let timezone: SourceTimezone = timezone.parse()?;
+    let mut state_guard = state.lock().unwrap();
+    state_guard.set_source_timezone(&source_id, timezone)
+}
+
+/// Set the encoding a source's files are decoded in, e.g. "utf-8", "latin-1",
+/// "windows-1252", "utf-16le" or "utf-16be". Buffered entries are dropped.
+#[tauri::command]
+pub fn set_source_encoding(
+    state: State<SharedLogWatcherState>,
+    source_id: String,
+    encoding: String,
+) -> Result<LogSource, String> {
+    let encoding: TextEncoding = encoding.parse()?;
+    let mut state_guard = state.lock().unwrap();
+    state_guard.set_source_encoding(&source_id, encoding)
+}
+
+/// Set whether ANSI escapes are stripped from a source's lines.
+#[tauri::command]
+pub fn set_source_strip_ansi(
+    state: State<SharedLogWatcherState>,
+    source_id: String,
+    strip_ansi: bool,
+) -> Result<LogSource, String> {
+    let mut state_guard = state.lock().unwrap();
+    state_guard.set_source_strip_ansi(&source_id, strip_ansi)
+}
+
+/// Set the level names and numbers a source uses, replacing its aliases.
+#[tauri::command]
+pub fn set_source_level_aliases(
+    state: State<SharedLogWatcherState>,
+    source_id: String,
+    aliases: HashMap<String, LogLevel>,
+) -> Result<LogSource, String> {
+    let mut state_guard = state.lock().unwrap();
+    state_guard.set_source_level_aliases(&source_id, aliases.into_iter().collect())
+}
+
 /// Check if a directory contains Laravel daily logs.
 #[tauri::command]
 pub fn detect_laravel_logs(path: String) -> bool {
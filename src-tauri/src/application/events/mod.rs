@@ -48,6 +48,22 @@ pub struct FileTruncatedEvent {
     pub source_id: String,
 }
 
+/// Event payload for file rotated.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FileRotatedEvent {
+    /// The source ID.
+    pub source_id: String,
+}
+
+/// Event payload for a folder source moving on to a newer file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SourceSwitchedFileEvent {
+    /// The source ID.
+    pub source_id: String,
+    /// The file the source now follows.
+    pub path: String,
+}
+
 /// Event names for Tauri events.
 pub mod event_names {
     /// New log entries available.
@@ -60,4 +76,8 @@ pub mod event_names {
     pub const SOURCE_REMOVED: &str = "source-removed";
     /// File was truncated (cleared).
     pub const FILE_TRUNCATED: &str = "file-truncated";
+    /// File was replaced by a new one at the same path (log rotation).
+    pub const FILE_ROTATED: &str = "file-rotated";
+    /// Folder source started following a newer matching file.
+    pub const SOURCE_SWITCHED_FILE: &str = "source-switched-file";
 }
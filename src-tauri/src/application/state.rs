@@ -1,21 +1,55 @@
 //! Application state management.
 
-use std::collections::HashMap;
-use std::path::PathBuf;
-use std::sync::mpsc::Receiver;
+use std::borrow::Cow;
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError};
 use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
-use log::info;
+use log::{info, warn};
 use tauri::{AppHandle, Emitter};
 
 use crate::domain::log_watching::log_entry::LogEntry;
 use crate::domain::log_watching::log_source::{LogSource, LogSourceStatus};
 use crate::domain::log_watching::ports::{FileWatchEvent, FileWatcher};
 use crate::domain::log_watching::value_objects::file_path::FilePath;
-use crate::domain::parsing::{LaravelLogParser, LogParser};
-use crate::infrastructure::file_system::NotifyFileWatcher;
-
-use super::events::{event_names, FileTruncatedEvent, LogEntriesEvent, SourceStatusEvent};
+use crate::domain::log_watching::value_objects::level_aliases::LevelAliases;
+use crate::domain::log_watching::value_objects::source_timezone::SourceTimezone;
+use crate::domain::log_watching::value_objects::text_encoding::TextEncoding;
+use crate::domain::log_watching::value_objects::text_style::StyleSpan;
+use crate::domain::parsing::{
+    AccessLogParser, AnsiStripper, ApacheErrorLogParser, CriLogParser, CustomParserDefinition,
+    CustomRegexParser, DockerJsonLogParser, FallbackParser, FormatDetector, GenericParser,
+    GrokLibrary, JsonLinesParser, JvmLogParser, LaravelLogParser, LogParser, LogfmtParser,
+    NginxErrorLogParser, ParseContext, PatternSyntax, PayloadParsers, PythonLogParser,
+    RailsLogParser, SymfonyLogParser, SyslogParser,
+};
+use crate::infrastructure::file_system::{NotifyFileWatcher, DEFAULT_PARTIAL_LINE_TIMEOUT};
+use crate::infrastructure::storage::{
+    AppSettings, ParserConfig, ParserStore, SettingsStore, SourceSettings,
+};
+
+use super::events::{
+    event_names, FileRotatedEvent, FileTruncatedEvent, LogEntriesEvent, SourceStatusEvent,
+    SourceSwitchedFileEvent,
+};
+
+/// Maximum number of appended lines held back for a source, see
+/// [`LogWatcherState::parse_appended_lines`].
+const MAX_HELD_LINES: usize = 1000;
+
+/// How often held lines are checked against the partial line timeout.
+const HELD_LINES_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Lines of a source held back until the lines completing them are appended.
+struct HeldLines {
+    lines: Vec<(usize, String)>,
+    /// Whether their line numbers are estimated.
+    approximate: bool,
+    /// When the last of them was read.
+    since: Instant,
+}
 
 /// The application state for log watching.
 pub struct LogWatcherState {
@@ -27,10 +61,28 @@ pub struct LogWatcherState {
     path_to_source: HashMap<PathBuf, String>,
     /// Log entries per source.
     entries: HashMap<String, Vec<LogEntry>>,
+    /// Appended lines per source that wait for the lines completing them.
+    held_lines: HashMap<String, HeldLines>,
     /// Next source ID.
     next_id: u64,
     /// Available log parsers.
-    parsers: Vec<Box<dyn LogParser>>,
+    parsers: Vec<Arc<dyn LogParser>>,
+    /// Parsers container payloads are handed to, kept in step with the others.
+    payload_parsers: PayloadParsers,
+    /// Fallback parser tried after all others; never picked by detection.
+    generic_parser: GenericParser,
+    /// User-defined parsers, only used for sources they are assigned to.
+    custom_parsers: Vec<CustomRegexParser>,
+    /// Parser name assigned to each source path.
+    parser_assignments: HashMap<PathBuf, String>,
+    /// User-defined grok patterns, added on top of the standard library.
+    grok_patterns: BTreeMap<String, String>,
+    /// Settings chosen for each source path, when any differ from the defaults.
+    source_settings: HashMap<PathBuf, SourceSettings>,
+    /// Storage for custom parsers and assignments.
+    parser_store: Option<ParserStore>,
+    /// Storage for source settings and the partial line timeout.
+    settings_store: Option<SettingsStore>,
 }
 
 impl LogWatcherState {
@@ -39,17 +91,74 @@ impl LogWatcherState {
         let watcher =
             NotifyFileWatcher::new().map_err(|e| format!("Failed to create watcher: {}", e))?;
 
-        // Initialize with available parsers
-        let parsers: Vec<Box<dyn LogParser>> = vec![Box::new(LaravelLogParser::new())];
+        // Initialize with available parsers; container parsers unwrap lines
+        // and hand the payload to the format parsers
+        let payload_parsers = PayloadParsers::default();
+        let mut parsers: Vec<Arc<dyn LogParser>> = vec![
+            Arc::new(DockerJsonLogParser::new(payload_parsers.clone())),
+            Arc::new(CriLogParser::new(payload_parsers.clone())),
+        ];
+        parsers.extend(Self::format_parsers());
 
-        Ok(Self {
+        let state = Self {
             watcher,
             sources: HashMap::new(),
             path_to_source: HashMap::new(),
             entries: HashMap::new(),
+            held_lines: HashMap::new(),
             next_id: 1,
             parsers,
-        })
+            payload_parsers,
+            generic_parser: GenericParser::new(),
+            custom_parsers: Vec::new(),
+            parser_assignments: HashMap::new(),
+            grok_patterns: BTreeMap::new(),
+            source_settings: HashMap::new(),
+            parser_store: None,
+            settings_store: None,
+        };
+        state.update_payload_parsers();
+        Ok(state)
+    }
+
+    /// Create the built-in parsers for application log formats.
+    fn format_parsers() -> Vec<Arc<dyn LogParser>> {
+        vec![
+            Arc::new(SymfonyLogParser::new()),
+            Arc::new(LaravelLogParser::new()),
+            Arc::new(RailsLogParser::new()),
+            Arc::new(JsonLinesParser::new()),
+            Arc::new(AccessLogParser::new()),
+            Arc::new(NginxErrorLogParser::new()),
+            Arc::new(ApacheErrorLogParser::new()),
+            Arc::new(PythonLogParser::new()),
+            Arc::new(JvmLogParser::new()),
+            Arc::new(SyslogParser::new()),
+            Arc::new(LogfmtParser::new()),
+        ]
+    }
+
+    /// Hand container payloads to the format parsers, then the custom
+    /// parsers, then the generic parser.
+    ///
+    /// Called whenever the custom parsers change. The container parsers
+    /// themselves are left out, as payloads are not wrapped twice.
+    fn update_payload_parsers(&self) {
+        let parsers = self
+            .parsers
+            .iter()
+            .filter(|p| ![DockerJsonLogParser::NAME, CriLogParser::NAME].contains(&p.name()))
+            .cloned()
+            .chain(
+                self.custom_parsers
+                    .iter()
+                    .map(|p| Arc::new(p.clone()) as Arc<dyn LogParser>),
+            )
+            .chain(std::iter::once(
+                Arc::new(self.generic_parser.clone()) as Arc<dyn LogParser>
+            ))
+            .collect();
+        self.payload_parsers.set(parsers);
     }
 
     /// Generate a new unique source ID.
@@ -75,13 +184,16 @@ impl LogWatcherState {
             .map_err(|e| format!("Failed to watch file: {}", e))?;
 
         let id = self.generate_id();
-        let source = LogSource::new_file(id.clone(), file_path, name);
+        let mut source = LogSource::new_file(id.clone(), file_path, name);
+        source.parser = self.parser_assignments.get(&path_buf).cloned();
+        self.apply_source_settings(&mut source, &path_buf);
 
-        self.sources.insert(id.clone(), source.clone());
+        self.sources.insert(id.clone(), source);
         self.path_to_source.insert(path_buf, id.clone());
-        self.entries.insert(id, Vec::new());
+        self.entries.insert(id.clone(), Vec::new());
+        self.detect_source_parser(&id);
 
-        Ok(source)
+        Ok(self.sources[&id].clone())
     }
 
     /// Add a folder source.
@@ -105,13 +217,45 @@ impl LogWatcherState {
             .map_err(|e| format!("Failed to watch folder: {}", e))?;
 
         let id = self.generate_id();
-        let source = LogSource::new_folder(id.clone(), file_path, pattern.to_string(), name);
+        let mut source = LogSource::new_folder(id.clone(), file_path, pattern.to_string(), name);
+        source.parser = self.parser_assignments.get(&path_buf).cloned();
+        self.apply_source_settings(&mut source, &path_buf);
+        source.current_file = Self::latest_matching_file(&path_buf, Some(pattern))
+            .and_then(|file| FilePath::new(file).ok());
 
-        self.sources.insert(id.clone(), source.clone());
+        self.sources.insert(id.clone(), source);
         self.path_to_source.insert(path_buf, id.clone());
-        self.entries.insert(id, Vec::new());
+        self.entries.insert(id.clone(), Vec::new());
+        self.detect_source_parser(&id);
 
-        Ok(source)
+        Ok(self.sources[&id].clone())
+    }
+
+    /// Apply the settings stored for a newly watched path to its source.
+    fn apply_source_settings(&mut self, source: &mut LogSource, path: &Path) {
+        let settings = self.source_settings.get(path).cloned().unwrap_or_default();
+        source.timezone = settings.timezone;
+        source.strip_ansi = !settings.raw_ansi;
+        source.level_aliases = settings.level_aliases;
+        source.encoding = settings.encoding;
+        if settings.encoding != TextEncoding::default() {
+            if let Err(e) = self.watcher.set_encoding(path, settings.encoding) {
+                warn!(
+                    "Failed to decode {:?} as {}: {}",
+                    path, settings.encoding, e
+                );
+            }
+        }
+    }
+
+    /// Change the settings stored for a source path, dropping them once they
+    /// are back to the defaults.
+    fn update_source_settings(&mut self, path: PathBuf, update: impl FnOnce(&mut SourceSettings)) {
+        let settings = self.source_settings.entry(path.clone()).or_default();
+        update(settings);
+        if settings.is_default() {
+            self.source_settings.remove(&path);
+        }
     }
 
     /// Remove a source.
@@ -124,6 +268,7 @@ impl LogWatcherState {
         let path_buf: PathBuf = source.path.value().to_path_buf();
         self.path_to_source.remove(&path_buf);
         self.entries.remove(id);
+        self.held_lines.remove(id);
 
         // Try to unwatch, but don't fail if it doesn't work
         if let Err(e) = self.watcher.unwatch(&path_buf) {
@@ -142,6 +287,7 @@ impl LogWatcherState {
         self.sources.clear();
         self.path_to_source.clear();
         self.entries.clear();
+        self.held_lines.clear();
 
         info!("Cleared all sources");
     }
@@ -191,6 +337,7 @@ impl LogWatcherState {
         if let Some(entries) = self.entries.get_mut(source_id) {
             entries.clear();
         }
+        self.held_lines.remove(source_id);
     }
 
     /// Read initial file content.
@@ -205,49 +352,16 @@ impl LogWatcherState {
             .ok_or_else(|| "Source not found".to_string())?
             .clone();
 
-        let path = source.path.value().to_path_buf();
-
         let mut entries = Vec::new();
 
-        if source.is_folder() {
-            // For folder sources, read all matching files
-            if let Some(pattern) = &source.pattern {
-                if let Ok(glob) = glob::Pattern::new(pattern) {
-                    if let Ok(dir_entries) = std::fs::read_dir(&path) {
-                        // Collect matching files and sort by name (for Laravel logs this gives chronological order)
-                        let mut matching_files: Vec<PathBuf> = dir_entries
-                            .filter_map(|e| e.ok())
-                            .map(|e| e.path())
-                            .filter(|p| {
-                                p.is_file()
-                                    && p.file_name()
-                                        .map(|n| glob.matches(n.to_string_lossy().as_ref()))
-                                        .unwrap_or(false)
-                            })
-                            .collect();
-
-                        matching_files.sort();
-
-                        // Read from the most recent file (last in sorted order)
-                        if let Some(latest_file) = matching_files.last() {
-                            let lines = self
-                                .watcher
-                                .read_initial_content(latest_file, max_lines)
-                                .map_err(|e| format!("Failed to read file: {}", e))?;
-
-                            entries = self.parse_lines_multiline(&lines);
-                        }
-                    }
-                }
-            }
-        } else {
-            // For file sources, read directly
-            let lines = self
+        // Folder sources read the file they currently follow
+        if let Some(path) = Self::followed_file(&source) {
+            let content = self
                 .watcher
                 .read_initial_content(&path, max_lines)
                 .map_err(|e| format!("Failed to read file: {}", e))?;
 
-            entries = self.parse_lines_multiline(&lines);
+            entries = self.parse_initial_lines(source_id, content.lines, content.approximate);
         }
 
         // Store entries
@@ -263,23 +377,213 @@ impl LogWatcherState {
         Ok(entries)
     }
 
-    /// Parse a log line using available parsers.
-    fn parse_line(&self, line: &str, line_number: u64) -> LogEntry {
+    /// Find the most recent file in a folder matching a glob pattern.
+    fn latest_matching_file(path: &Path, pattern: Option<&str>) -> Option<PathBuf> {
+        let glob = glob::Pattern::new(pattern?).ok()?;
+        let dir_entries = std::fs::read_dir(path).ok()?;
+
+        // Sort by name (for Laravel logs this gives chronological order)
+        let mut matching_files: Vec<PathBuf> = dir_entries
+            .filter_map(|e| e.ok())
+            .map(|e| e.path())
+            .filter(|p| {
+                p.is_file()
+                    && p.file_name()
+                        .map(|n| glob.matches(n.to_string_lossy().as_ref()))
+                        .unwrap_or(false)
+            })
+            .collect();
+
+        matching_files.sort();
+        matching_files.pop()
+    }
+
+    /// The file a source reads: its own path, or a folder's current file.
+    fn followed_file(source: &LogSource) -> Option<PathBuf> {
+        if source.is_folder() {
+            source
+                .current_file
+                .as_ref()
+                .map(|file| file.value().to_path_buf())
+        } else {
+            Some(source.path.value().to_path_buf())
+        }
+    }
+
+    /// Parse initial lines, detecting the format first if the source has no parser yet.
+    ///
+    /// Trailing lines that only start an entry are held back, as for
+    /// appended lines.
+    fn parse_initial_lines(
+        &mut self,
+        source_id: &str,
+        mut lines: Vec<(usize, String)>,
+        approximate: bool,
+    ) -> Vec<LogEntry> {
+        let sample: Vec<&str> = lines.iter().map(|(_, s)| s.as_str()).collect();
+        self.detect_parser(source_id, &sample);
+        self.held_lines.remove(source_id);
+        self.hold_unfinished_lines(source_id, &mut lines, approximate);
+        self.parse_numbered_lines(source_id, &lines, approximate)
+    }
+
+    /// Get all parsers that format detection can pick from.
+    fn detection_candidates(&self) -> Vec<&dyn LogParser> {
+        self.parsers
+            .iter()
+            .map(|p| p.as_ref())
+            .chain(self.custom_parsers.iter().map(|p| p as &dyn LogParser))
+            .collect()
+    }
+
+    /// Pin the best matching parser to a source that has none, based on sample lines.
+    fn detect_parser(&mut self, source_id: &str, lines: &[&str]) {
+        if self
+            .sources
+            .get(source_id)
+            .map_or(true, |s| s.parser.is_some())
+        {
+            return;
+        }
+
+        let parse_context = self.parse_context(source_id);
+        let stripped: Vec<Cow<str>> = lines
+            .iter()
+            .map(|line| Self::strip_ansi(line, &parse_context).0)
+            .collect();
+        let lines: Vec<&str> = stripped.iter().map(|s| s.as_ref()).collect();
+
+        let Some(name) = FormatDetector::detect(&self.detection_candidates(), &lines)
+            .map(|p| p.name().to_string())
+        else {
+            return;
+        };
+
+        info!("Detected {} format for source {}", name, source_id);
+        if let Some(source) = self.sources.get_mut(source_id) {
+            source.parser = Some(name);
+            source.parser_detected = true;
+        }
+    }
+
+    /// Sample the source's file and pin the detected parser, if it has none yet.
+    fn detect_source_parser(&mut self, source_id: &str) {
+        let Some(source) = self.sources.get(source_id) else {
+            return;
+        };
+
+        let Some(file) = Self::followed_file(source) else {
+            return;
+        };
+
+        match self
+            .watcher
+            .read_sample(&file, FormatDetector::SAMPLE_LINES)
+        {
+            Ok(lines) => {
+                let sample: Vec<&str> = lines.iter().map(|s| s.as_str()).collect();
+                self.detect_parser(source_id, &sample);
+            }
+            Err(e) => warn!("Failed to sample {:?} for format detection: {}", file, e),
+        }
+    }
+
+    /// Find a built-in or custom parser by name.
+    fn find_parser(&self, name: &str) -> Option<&dyn LogParser> {
+        self.parsers
+            .iter()
+            .map(|p| p.as_ref())
+            .chain(std::iter::once(&self.generic_parser as &dyn LogParser))
+            .chain(self.custom_parsers.iter().map(|p| p as &dyn LogParser))
+            .find(|p| p.name() == name)
+    }
+
+    /// Get the parsers to try for a source.
+    ///
+    /// A source with a pinned parser only uses that parser; otherwise all
+    /// built-in parsers are tried in order. The generic parser comes last
+    /// in both cases.
+    fn parsers_for_source(&self, source_id: &str) -> Vec<&dyn LogParser> {
+        let pinned = self
+            .sources
+            .get(source_id)
+            .and_then(|s| s.parser.as_deref())
+            .and_then(|name| self.find_parser(name));
+
+        let mut parsers = match pinned {
+            Some(parser) => vec![parser],
+            None => self.parsers.iter().map(|p| p.as_ref()).collect(),
+        };
+        if parsers
+            .iter()
+            .all(|p| p.name() != self.generic_parser.name())
+        {
+            parsers.push(&self.generic_parser);
+        }
+        parsers
+    }
+
+    /// Get the parse settings for a source.
+    fn parse_context(&self, source_id: &str) -> ParseContext {
+        self.sources
+            .get(source_id)
+            .map(ParseContext::for_source)
+            .unwrap_or_default()
+    }
+
+    /// Strip ANSI escapes from a line if the source asks for it.
+    ///
+    /// Returns the text to parse and its style ranges.
+    fn strip_ansi<'a>(
+        line: &'a str,
+        parse_context: &ParseContext,
+    ) -> (Cow<'a, str>, Vec<StyleSpan>) {
+        let stripper = AnsiStripper::new();
+        if !parse_context.strip_ansi || !stripper.has_escapes(line) {
+            return (Cow::Borrowed(line), Vec::new());
+        }
+        let (text, styles) = stripper.strip(line);
+        (Cow::Owned(text), styles)
+    }
+
+    /// Parse a log line using the parsers for a source.
+    fn parse_line(&self, source_id: &str, line: &str, line_number: u64) -> LogEntry {
+        let parse_context = self.parse_context(source_id);
+        let (line, styles) = Self::strip_ansi(line, &parse_context);
+
+        self.parse_clean_line(source_id, &line, line_number, &parse_context)
+            .with_styles(styles)
+    }
+
+    /// Parse a line that has already been stripped of ANSI escapes.
+    fn parse_clean_line(
+        &self,
+        source_id: &str,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> LogEntry {
         // Try each parser
-        for parser in &self.parsers {
-            if let Some(entry) = parser.parse(line, line_number) {
+        for parser in self.parsers_for_source(source_id) {
+            if let Some(entry) = parser.parse_with(line, line_number, parse_context) {
                 return entry;
             }
         }
 
-        // Fall back to raw entry
-        LogEntry::from_raw(line.to_string(), line_number)
+        // Fall back to whatever can be detected in the line
+        FallbackParser::new().parse(line, line_number, parse_context)
     }
 
     /// Parse multiple lines with multiline support (for stacktraces, etc.).
-    fn parse_lines_multiline(&self, lines: &[(usize, String)]) -> Vec<LogEntry> {
+    fn parse_lines_multiline(&self, source_id: &str, lines: &[(usize, String)]) -> Vec<LogEntry> {
+        let parsers = self.parsers_for_source(source_id);
+        let parse_context = self.parse_context(source_id);
         let mut entries = Vec::new();
-        let line_refs: Vec<&str> = lines.iter().map(|(_, s)| s.as_str()).collect();
+        let stripped: Vec<(Cow<str>, Vec<StyleSpan>)> = lines
+            .iter()
+            .map(|(_, s)| Self::strip_ansi(s, &parse_context))
+            .collect();
+        let line_refs: Vec<&str> = stripped.iter().map(|(s, _)| s.as_ref()).collect();
         let mut i = 0;
 
         while i < line_refs.len() {
@@ -288,11 +592,12 @@ impl LogWatcherState {
 
             // Try multiline parsing first
             let mut parsed = false;
-            for parser in &self.parsers {
+            for parser in &parsers {
                 if parser.can_parse(remaining[0]) {
-                    if let Some((entry, consumed)) = parser.parse_multiline(remaining, line_number)
+                    if let Some((entry, consumed)) =
+                        parser.parse_multiline_with(remaining, line_number, &parse_context)
                     {
-                        entries.push(entry);
+                        entries.push(entry.with_styles(stripped[i].1.clone()));
                         i += consumed;
                         parsed = true;
                         break;
@@ -302,7 +607,9 @@ impl LogWatcherState {
 
             // Fall back to single-line parsing
             if !parsed {
-                entries.push(self.parse_line(remaining[0], line_number));
+                let entry =
+                    self.parse_clean_line(source_id, remaining[0], line_number, &parse_context);
+                entries.push(entry.with_styles(stripped[i].1.clone()));
                 i += 1;
             }
         }
@@ -310,6 +617,475 @@ impl LogWatcherState {
         entries
     }
 
+    /// Parse lines appended to a source, with multiline support.
+    ///
+    /// Trailing lines that only start an entry, such as CRI `P` lines, are
+    /// held back until the lines completing them are appended, up to
+    /// [`MAX_HELD_LINES`], or until [`Self::flush_held_lines`] gives up
+    /// waiting for them.
+    pub fn parse_appended_lines(
+        &mut self,
+        source_id: &str,
+        lines: Vec<(usize, String)>,
+        approximate: bool,
+    ) -> Vec<LogEntry> {
+        let mut held = self
+            .held_lines
+            .remove(source_id)
+            .map(|held| held.lines)
+            .unwrap_or_default();
+        held.extend(lines);
+        let mut lines = held;
+
+        self.hold_unfinished_lines(source_id, &mut lines, approximate);
+        self.parse_numbered_lines(source_id, &lines, approximate)
+    }
+
+    /// Parse the lines held for each source that have waited `timeout` for
+    /// the lines completing them, returning their entries by source ID.
+    pub fn flush_held_lines(&mut self, timeout: Duration) -> Vec<(String, Vec<LogEntry>)> {
+        let expired: Vec<String> = self
+            .held_lines
+            .iter()
+            .filter(|(_, held)| held.since.elapsed() >= timeout)
+            .map(|(source_id, _)| source_id.clone())
+            .collect();
+
+        let mut flushed = Vec::new();
+        for source_id in expired {
+            let Some(held) = self.held_lines.remove(&source_id) else {
+                continue;
+            };
+            let entries = self.parse_numbered_lines(&source_id, &held.lines, held.approximate);
+            if !entries.is_empty() {
+                flushed.push((source_id, entries));
+            }
+        }
+        flushed
+    }
+
+    /// Parse lines with multiline support, marking whether their line
+    /// numbers are estimated.
+    fn parse_numbered_lines(
+        &self,
+        source_id: &str,
+        lines: &[(usize, String)],
+        approximate: bool,
+    ) -> Vec<LogEntry> {
+        let mut entries = self.parse_lines_multiline(source_id, lines);
+        for entry in &mut entries {
+            entry.line_number_approximate = approximate;
+        }
+        entries
+    }
+
+    /// Move trailing lines that only start an entry from `lines` to the
+    /// source's held lines, unless there are [`MAX_HELD_LINES`] of them.
+    fn hold_unfinished_lines(
+        &mut self,
+        source_id: &str,
+        lines: &mut Vec<(usize, String)>,
+        approximate: bool,
+    ) {
+        let parsers = self.parsers_for_source(source_id);
+        let unfinished = lines
+            .iter()
+            .rev()
+            .take_while(|(_, line)| parsers.iter().any(|p| p.continues_on_next_line(line)))
+            .count();
+        if unfinished > 0 && unfinished < MAX_HELD_LINES {
+            let held = HeldLines {
+                lines: lines.split_off(lines.len() - unfinished),
+                approximate,
+                since: Instant::now(),
+            };
+            self.held_lines.insert(source_id.to_string(), held);
+        }
+    }
+
+    /// Load custom parsers and source assignments from a store.
+    ///
+    /// Invalid stored definitions are skipped with a warning.
+    pub fn load_parser_config(&mut self, store: ParserStore) -> Result<(), String> {
+        let config = store
+            .load()
+            .map_err(|e| format!("Failed to load parser config: {}", e))?;
+
+        let mut library = GrokLibrary::standard();
+        self.grok_patterns = config
+            .grok_patterns
+            .into_iter()
+            .filter(|(name, definition)| {
+                library
+                    .add_pattern(name, definition)
+                    .map_err(|e| log::warn!("Skipping grok pattern {}: {}", name, e))
+                    .is_ok()
+            })
+            .collect();
+
+        self.custom_parsers = config
+            .custom_parsers
+            .into_iter()
+            .filter_map(|definition| {
+                let name = definition.name.clone();
+                CustomRegexParser::with_library(definition, &library)
+                    .map_err(|e| log::warn!("Skipping custom parser {}: {}", name, e))
+                    .ok()
+            })
+            .collect();
+        self.parser_assignments = config.assignments;
+        self.update_payload_parsers();
+        self.parser_store = Some(store);
+
+        info!("Loaded {} custom parsers", self.custom_parsers.len());
+        Ok(())
+    }
+
+    /// Load source settings and the partial line timeout from a store.
+    pub fn load_settings(&mut self, store: SettingsStore) -> Result<(), String> {
+        let settings = store
+            .load()
+            .map_err(|e| format!("Failed to load settings: {}", e))?;
+
+        self.source_settings = settings.sources;
+        if let Some(timeout_ms) = settings.partial_line_timeout_ms {
+            self.watcher
+                .set_partial_line_timeout(Duration::from_millis(timeout_ms));
+        }
+        self.settings_store = Some(store);
+        Ok(())
+    }
+
+    /// Persist source settings and the partial line timeout, if a store is configured.
+    fn save_settings(&self) -> Result<(), String> {
+        let Some(store) = &self.settings_store else {
+            return Ok(());
+        };
+
+        let settings = AppSettings {
+            sources: self.source_settings.clone(),
+            partial_line_timeout_ms: Some(self.watcher.partial_line_timeout())
+                .filter(|timeout| *timeout != DEFAULT_PARTIAL_LINE_TIMEOUT)
+                .map(|timeout| timeout.as_millis() as u64),
+        };
+        store
+            .save(&settings)
+            .map_err(|e| format!("Failed to save settings: {}", e))
+    }
+
+    /// Persist custom parsers and source assignments, if a store is configured.
+    fn save_parser_config(&self) -> Result<(), String> {
+        let Some(store) = &self.parser_store else {
+            return Ok(());
+        };
+
+        let config = ParserConfig {
+            custom_parsers: self
+                .custom_parsers
+                .iter()
+                .map(|p| p.definition().clone())
+                .collect(),
+            assignments: self.parser_assignments.clone(),
+            grok_patterns: self.grok_patterns.clone(),
+        };
+        store
+            .save(&config)
+            .map_err(|e| format!("Failed to save parser config: {}", e))
+    }
+
+    /// Register or replace a custom parser.
+    pub fn register_custom_parser(
+        &mut self,
+        definition: CustomParserDefinition,
+    ) -> Result<(), String> {
+        if definition.name == self.generic_parser.name()
+            || self.parsers.iter().any(|p| p.name() == definition.name)
+        {
+            return Err(format!("Parser name is reserved: {}", definition.name));
+        }
+
+        let library = Self::build_grok_library(&self.grok_patterns)?;
+        let parser =
+            CustomRegexParser::with_library(definition, &library).map_err(|e| e.to_string())?;
+        match self
+            .custom_parsers
+            .iter_mut()
+            .find(|p| p.name() == parser.name())
+        {
+            Some(existing) => *existing = parser,
+            None => self.custom_parsers.push(parser),
+        }
+        self.update_payload_parsers();
+
+        self.save_parser_config()
+    }
+
+    /// Remove a custom parser and unassign it from all sources.
+    pub fn remove_custom_parser(&mut self, name: &str) -> Result<(), String> {
+        let index = self
+            .custom_parsers
+            .iter()
+            .position(|p| p.name() == name)
+            .ok_or_else(|| "Parser not found".to_string())?;
+        self.custom_parsers.remove(index);
+        self.update_payload_parsers();
+
+        self.parser_assignments
+            .retain(|_, assigned| assigned != name);
+        for source in self.sources.values_mut() {
+            if source.parser.as_deref() == Some(name) {
+                source.parser = None;
+                source.parser_detected = false;
+            }
+        }
+
+        self.save_parser_config()
+    }
+
+    /// Get all custom parser definitions.
+    pub fn get_custom_parsers(&self) -> Vec<CustomParserDefinition> {
+        self.custom_parsers
+            .iter()
+            .map(|p| p.definition().clone())
+            .collect()
+    }
+
+    /// Build the grok library from the standard patterns and the given user patterns.
+    fn build_grok_library(patterns: &BTreeMap<String, String>) -> Result<GrokLibrary, String> {
+        let mut library = GrokLibrary::standard();
+        for (name, definition) in patterns {
+            library
+                .add_pattern(name, definition)
+                .map_err(|e| e.to_string())?;
+        }
+        Ok(library)
+    }
+
+    /// Rebuild the grok-based custom parsers against a new pattern set.
+    ///
+    /// Nothing is changed if any pattern or parser fails to compile.
+    fn apply_grok_patterns(&mut self, patterns: BTreeMap<String, String>) -> Result<(), String> {
+        let library = Self::build_grok_library(&patterns)?;
+        for name in patterns.keys() {
+            library
+                .compile(&format!("%{{{}}}", name))
+                .map_err(|e| e.to_string())?;
+        }
+
+        let parsers = self
+            .custom_parsers
+            .iter()
+            .map(|parser| match parser.definition().syntax {
+                PatternSyntax::Regex => Ok(parser.clone()),
+                PatternSyntax::Grok => {
+                    CustomRegexParser::with_library(parser.definition().clone(), &library)
+                        .map_err(|e| format!("Parser {} would break: {}", parser.name(), e))
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        self.grok_patterns = patterns;
+        self.custom_parsers = parsers;
+        self.update_payload_parsers();
+        self.save_parser_config()
+    }
+
+    /// Register or replace a user-defined grok pattern.
+    pub fn register_grok_pattern(&mut self, name: &str, definition: &str) -> Result<(), String> {
+        let mut patterns = self.grok_patterns.clone();
+        patterns.insert(name.to_string(), definition.to_string());
+        self.apply_grok_patterns(patterns)
+    }
+
+    /// Remove a user-defined grok pattern.
+    pub fn remove_grok_pattern(&mut self, name: &str) -> Result<(), String> {
+        let mut patterns = self.grok_patterns.clone();
+        if patterns.remove(name).is_none() {
+            return Err("Grok pattern not found".to_string());
+        }
+        self.apply_grok_patterns(patterns)
+    }
+
+    /// Get all user-defined grok patterns.
+    pub fn get_grok_patterns(&self) -> BTreeMap<String, String> {
+        self.grok_patterns.clone()
+    }
+
+    /// Get the names of all built-in and custom parsers.
+    pub fn get_parser_names(&self) -> Vec<String> {
+        self.detection_candidates()
+            .iter()
+            .map(|p| p.name().to_string())
+            .chain(std::iter::once(self.generic_parser.name().to_string()))
+            .collect()
+    }
+
+    /// Assign a parser to a source, overriding detection.
+    ///
+    /// Passing None clears the assignment and detects the format again.
+    pub fn set_source_parser(
+        &mut self,
+        source_id: &str,
+        parser: Option<String>,
+    ) -> Result<LogSource, String> {
+        if let Some(name) = &parser {
+            if self.find_parser(name).is_none() {
+                return Err(format!("Unknown parser: {}", name));
+            }
+        }
+
+        let source = self
+            .sources
+            .get_mut(source_id)
+            .ok_or_else(|| "Source not found".to_string())?;
+        source.parser = parser.clone();
+        source.parser_detected = false;
+
+        let path = source.path.value().to_path_buf();
+        match parser {
+            Some(name) => self.parser_assignments.insert(path, name),
+            None => self.parser_assignments.remove(&path),
+        };
+        self.detect_source_parser(source_id);
+
+        self.save_parser_config()?;
+        Ok(self.sources[source_id].clone())
+    }
+
+    /// Set the timezone a source writes naive timestamps in.
+    ///
+    /// Buffered entries are parsed again from their raw line so their
+    /// timestamps move to the new timezone; the rest of each entry is kept.
+    pub fn set_source_timezone(
+        &mut self,
+        source_id: &str,
+        timezone: SourceTimezone,
+    ) -> Result<LogSource, String> {
+        let source = self
+            .sources
+            .get_mut(source_id)
+            .ok_or_else(|| "Source not found".to_string())?;
+        source.timezone = timezone;
+
+        let path = source.path.value().to_path_buf();
+        self.update_source_settings(path, |settings| settings.timezone = timezone);
+
+        let entries = self.entries.remove(source_id).unwrap_or_default();
+        let entries = entries
+            .into_iter()
+            .map(|mut entry| {
+                let reparsed = self.parse_line(source_id, &entry.raw, entry.line_number);
+                if reparsed.timestamp.is_some() {
+                    entry.timestamp = reparsed.timestamp;
+                }
+                entry
+            })
+            .collect();
+        self.entries.insert(source_id.to_string(), entries);
+
+        self.save_settings()?;
+        Ok(self.sources[source_id].clone())
+    }
+
+    /// Set whether ANSI escapes are stripped from a source's lines.
+    ///
+    /// Applies to lines read from now on.
+    pub fn set_source_strip_ansi(
+        &mut self,
+        source_id: &str,
+        strip_ansi: bool,
+    ) -> Result<LogSource, String> {
+        let source = self
+            .sources
+            .get_mut(source_id)
+            .ok_or_else(|| "Source not found".to_string())?;
+        source.strip_ansi = strip_ansi;
+
+        let path = source.path.value().to_path_buf();
+        self.update_source_settings(path, |settings| settings.raw_ansi = !strip_ansi);
+
+        self.save_settings()?;
+        Ok(self.sources[source_id].clone())
+    }
+
+    /// Set the encoding a source's files are decoded in.
+    ///
+    /// Buffered entries were decoded in the old encoding, so they are
+    /// dropped; read the initial content again to see them decoded anew.
+    pub fn set_source_encoding(
+        &mut self,
+        source_id: &str,
+        encoding: TextEncoding,
+    ) -> Result<LogSource, String> {
+        let source = self
+            .sources
+            .get_mut(source_id)
+            .ok_or_else(|| "Source not found".to_string())?;
+        let path = source.path.value().to_path_buf();
+
+        self.watcher
+            .set_encoding(&path, encoding)
+            .map_err(|e| format!("Failed to set encoding: {}", e))?;
+        source.encoding = encoding;
+
+        self.update_source_settings(path, |settings| settings.encoding = encoding);
+        self.clear_entries(source_id);
+
+        self.save_settings()?;
+        Ok(self.sources[source_id].clone())
+    }
+
+    /// Set the level names and numbers a source uses, replacing its aliases.
+    ///
+    /// Buffered entries are parsed again from their raw line to pick up the
+    /// new levels; the rest of each entry is kept.
+    pub fn set_source_level_aliases(
+        &mut self,
+        source_id: &str,
+        level_aliases: LevelAliases,
+    ) -> Result<LogSource, String> {
+        if level_aliases.iter().any(|(token, _)| token.is_empty()) {
+            return Err("Level alias cannot be empty".to_string());
+        }
+
+        let source = self
+            .sources
+            .get_mut(source_id)
+            .ok_or_else(|| "Source not found".to_string())?;
+        source.level_aliases = level_aliases.clone();
+
+        let path = source.path.value().to_path_buf();
+        self.update_source_settings(path, |settings| settings.level_aliases = level_aliases);
+
+        let entries = self.entries.remove(source_id).unwrap_or_default();
+        let entries = entries
+            .into_iter()
+            .map(|mut entry| {
+                entry.level = self
+                    .parse_line(source_id, &entry.raw, entry.line_number)
+                    .level;
+                entry
+            })
+            .collect();
+        self.entries.insert(source_id.to_string(), entries);
+
+        self.save_settings()?;
+        Ok(self.sources[source_id].clone())
+    }
+
+    /// Get how long a half-written line waits for its newline, in milliseconds.
+    pub fn get_partial_line_timeout(&self) -> u64 {
+        self.watcher.partial_line_timeout().as_millis() as u64
+    }
+
+    /// Set how long a half-written line waits for its newline before it is
+    /// shown as it is.
+    pub fn set_partial_line_timeout(&mut self, timeout_ms: u64) -> Result<(), String> {
+        self.watcher
+            .set_partial_line_timeout(Duration::from_millis(timeout_ms));
+        self.save_settings()
+    }
+
     /// Take the event receiver for processing file events.
     pub fn take_event_receiver(&mut self) -> Option<Receiver<FileWatchEvent>> {
         self.watcher.take_event_receiver()
@@ -347,6 +1123,45 @@ impl LogWatcherState {
         None
     }
 
+    /// Get the source a file's changes are shown in.
+    ///
+    /// Files in a folder other than the one the folder source follows are
+    /// ignored, so older files of a daily log do not mix with the current one.
+    pub fn get_followed_source_id(&self, path: &PathBuf) -> Option<String> {
+        let source_id = self.get_source_id_for_path(path)?;
+        let source = self.sources.get(&source_id)?;
+        if source.is_folder()
+            && source.current_file.as_ref().map(|file| file.value()) != Some(path.as_path())
+        {
+            return None;
+        }
+        Some(source_id)
+    }
+
+    /// Switch a folder source to a newly created file, if it is newer than
+    /// the one followed so far.
+    ///
+    /// Files are compared by name, as when the source was added. The
+    /// source's entries are cleared, since line numbers restart in the new
+    /// file. Returns the ID of the switched source.
+    pub fn switch_folder_file(&mut self, path: &PathBuf) -> Option<String> {
+        let source_id = self.get_source_id_for_path(path)?;
+        let source = self.sources.get_mut(&source_id)?;
+        if !source.is_folder()
+            || source
+                .current_file
+                .as_ref()
+                .is_some_and(|file| file.value() >= path.as_path())
+        {
+            return None;
+        }
+
+        source.current_file = Some(FilePath::new(path).ok()?);
+        self.clear_entries(&source_id);
+        info!("Source {} switched to {:?}", source_id, path);
+        Some(source_id)
+    }
+
     /// Add entries to a source.
     pub fn add_entries(&mut self, source_id: &str, new_entries: Vec<LogEntry>) {
         if let Some(entries) = self.entries.get_mut(source_id) {
@@ -375,14 +1190,51 @@ pub fn start_event_processor(app_handle: AppHandle, state: SharedLogWatcherState
     };
 
     if let Some(rx) = event_rx {
-        std::thread::spawn(move || {
-            for event in rx {
-                process_file_event(&app_handle, &state, event);
+        std::thread::spawn(move || loop {
+            match rx.recv_timeout(HELD_LINES_CHECK_INTERVAL) {
+                Ok(event) => process_file_event(&app_handle, &state, event),
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
             }
+            flush_held_lines(&app_handle, &state);
         });
     }
 }
 
+/// Emit the entries of held lines that waited the partial line timeout,
+/// as for lines the watcher sends without their newline.
+fn flush_held_lines(app_handle: &AppHandle, state: &SharedLogWatcherState) {
+    let mut state_guard = state.lock().unwrap();
+    let timeout = Duration::from_millis(state_guard.get_partial_line_timeout());
+    for (source_id, entries) in state_guard.flush_held_lines(timeout) {
+        state_guard.add_entries(&source_id, entries.clone());
+        let _ = app_handle.emit(
+            event_names::LOG_ENTRIES,
+            LogEntriesEvent { source_id, entries },
+        );
+    }
+}
+
+/// Set a source in error back to active, as its file is readable again.
+fn recover_source(app_handle: &AppHandle, state: &mut LogWatcherState, source_id: &str) {
+    let recovered = state
+        .get_source(source_id)
+        .is_some_and(|source| source.status == LogSourceStatus::Error);
+    if recovered {
+        state
+            .update_status(source_id, LogSourceStatus::Active, None)
+            .ok();
+        let _ = app_handle.emit(
+            event_names::SOURCE_STATUS,
+            SourceStatusEvent {
+                source_id: source_id.to_string(),
+                status: LogSourceStatus::Active,
+                error_message: None,
+            },
+        );
+    }
+}
+
 /// Process a file watch event.
 fn process_file_event(
     app_handle: &AppHandle,
@@ -392,22 +1244,18 @@ fn process_file_event(
     match event {
         FileWatchEvent::ContentAppended {
             path,
-            content,
-            line_number,
+            lines,
+            first_line_number,
+            approximate,
         } => {
             let mut state_guard = state.lock().unwrap();
-            if let Some(source_id) = state_guard.get_source_id_for_path(&path) {
+            if let Some(source_id) = state_guard.get_followed_source_id(&path) {
                 // Parse the new content
-                let entries: Vec<LogEntry> = content
-                    .lines()
-                    .enumerate()
-                    .map(|(i, line)| {
-                        state_guard.parse_line(
-                            line,
-                            (line_number - content.lines().count() + i + 1) as u64,
-                        )
-                    })
-                    .collect();
+                let lines = (first_line_number..).zip(lines).collect();
+                let entries = state_guard.parse_appended_lines(&source_id, lines, approximate);
+                if entries.is_empty() {
+                    return;
+                }
 
                 state_guard.add_entries(&source_id, entries.clone());
 
@@ -420,7 +1268,7 @@ fn process_file_event(
         }
         FileWatchEvent::FileTruncated { path } => {
             let mut state_guard = state.lock().unwrap();
-            if let Some(source_id) = state_guard.get_source_id_for_path(&path) {
+            if let Some(source_id) = state_guard.get_followed_source_id(&path) {
                 state_guard.clear_entries(&source_id);
                 let _ = app_handle.emit(
                     event_names::FILE_TRUNCATED,
@@ -428,9 +1276,19 @@ fn process_file_event(
                 );
             }
         }
+        FileWatchEvent::FileRotated { path } => {
+            let mut state_guard = state.lock().unwrap();
+            if let Some(source_id) = state_guard.get_followed_source_id(&path) {
+                // Lines of the new file are numbered from 1 again
+                state_guard.clear_entries(&source_id);
+                // The file is back if it was deleted before being recreated
+                recover_source(app_handle, &mut state_guard, &source_id);
+                let _ = app_handle.emit(event_names::FILE_ROTATED, FileRotatedEvent { source_id });
+            }
+        }
         FileWatchEvent::FileDeleted { path } => {
             let mut state_guard = state.lock().unwrap();
-            if let Some(source_id) = state_guard.get_source_id_for_path(&path) {
+            if let Some(source_id) = state_guard.get_followed_source_id(&path) {
                 state_guard
                     .update_status(
                         &source_id,
@@ -450,7 +1308,7 @@ fn process_file_event(
         }
         FileWatchEvent::Error { path, message } => {
             let mut state_guard = state.lock().unwrap();
-            if let Some(source_id) = state_guard.get_source_id_for_path(&path) {
+            if let Some(source_id) = state_guard.get_followed_source_id(&path) {
                 state_guard
                     .update_status(&source_id, LogSourceStatus::Error, Some(message.clone()))
                     .ok();
@@ -466,9 +1324,135 @@ fn process_file_event(
         }
         FileWatchEvent::FileCreated { path } => {
             info!("File created: {:?}", path);
+            let mut state_guard = state.lock().unwrap();
+            if let Some(source_id) = state_guard.switch_folder_file(&path) {
+                recover_source(app_handle, &mut state_guard, &source_id);
+                let _ = app_handle.emit(
+                    event_names::SOURCE_SWITCHED_FILE,
+                    SourceSwitchedFileEvent {
+                        source_id,
+                        path: path.to_string_lossy().to_string(),
+                    },
+                );
+            }
         }
-        FileWatchEvent::FileRenamed { from, to } => {
-            info!("File renamed: {:?} -> {:?}", from, to);
-        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::log_watching::LogLevel;
+    use tempfile::tempdir;
+
+    /// Watch a CRI log file holding one line, returning the state and source ID.
+    fn watch_cri_log(dir: &Path) -> (LogWatcherState, String) {
+        let path = dir.join("pod.log");
+        std::fs::write(
+            &path,
+            "2024-01-15T10:30:00.1Z stdout F {\"level\":\"info\",\"msg\":\"Started\"}\n",
+        )
+        .unwrap();
+
+        let mut state = LogWatcherState::new().unwrap();
+        let source = state.add_file(path.to_str().unwrap(), None).unwrap();
+        state
+            .set_source_parser(&source.id, Some(CriLogParser::NAME.to_string()))
+            .unwrap();
+        (state, source.id)
+    }
+
+    #[test]
+    fn test_reassemble_cri_partial_lines_across_appends() {
+        let dir = tempdir().unwrap();
+        let (mut state, id) = watch_cri_log(dir.path());
+
+        let entries = state.parse_appended_lines(
+            &id,
+            vec![(
+                2,
+                r#"2024-01-15T10:30:01.1Z stdout P {"level":"warn","#.to_string(),
+            )],
+            false,
+        );
+        assert!(entries.is_empty());
+
+        let entries = state.parse_appended_lines(
+            &id,
+            vec![
+                (
+                    3,
+                    r#"2024-01-15T10:30:01.1Z stdout F "msg":"Slow query"}"#.to_string(),
+                ),
+                (4, "2024-01-15T10:30:02.1Z stderr F plain text".to_string()),
+            ],
+            false,
+        );
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].level, LogLevel::Warning);
+        assert_eq!(entries[0].message, "Slow query");
+        assert_eq!(entries[0].line_number, 2);
+        assert_eq!(entries[1].message, "plain text");
+        assert_eq!(entries[1].line_number, 4);
+    }
+
+    #[test]
+    fn test_custom_parser_for_container_payload() {
+        let dir = tempdir().unwrap();
+        let (mut state, id) = watch_cri_log(dir.path());
+        state
+            .register_custom_parser(CustomParserDefinition {
+                name: "Billing".to_string(),
+                pattern: r"^<(?P<level>\w+)> \[(?P<channel>\w+)\] (?P<message>.*)$".to_string(),
+                syntax: PatternSyntax::Regex,
+                timestamp_format: None,
+                continuation_pattern: None,
+            })
+            .unwrap();
+
+        let entries = state.parse_appended_lines(
+            &id,
+            vec![(
+                2,
+                "2024-01-15T10:30:01.1Z stdout F <WARN> [invoices] Payment retried".to_string(),
+            )],
+            false,
+        );
+
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].level, LogLevel::Warning);
+        assert_eq!(entries[0].channel.as_deref(), Some("invoices"));
+        assert_eq!(entries[0].message, "Payment retried");
+    }
+
+    #[test]
+    fn test_flush_trailing_cri_partial_line() {
+        let dir = tempdir().unwrap();
+        let (mut state, id) = watch_cri_log(dir.path());
+
+        let entries = state.parse_appended_lines(
+            &id,
+            vec![(
+                2,
+                "2024-01-15T10:30:01.1Z stdout P Shutting down after".to_string(),
+            )],
+            true,
+        );
+        assert!(entries.is_empty());
+
+        // Still waiting for the rest of the line
+        assert!(state.flush_held_lines(Duration::from_secs(60)).is_empty());
+
+        // The container stopped, so the fragment is shown as it is
+        let flushed = state.flush_held_lines(Duration::ZERO);
+        assert_eq!(flushed.len(), 1);
+        let (source_id, entries) = &flushed[0];
+        assert_eq!(source_id, &id);
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].message, "Shutting down after");
+        assert_eq!(entries[0].line_number, 2);
+        assert!(entries[0].line_number_approximate);
+
+        assert!(state.flush_held_lines(Duration::ZERO).is_empty());
     }
 }
@@ -3,7 +3,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::domain::log_watching::LogLevel;
+use crate::domain::log_watching::{ExceptionInfo, LogLevel, StackFrame, StyleSpan};
 
 /// A parsed log entry from a log file.
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,15 +20,31 @@ pub struct LogEntry {
     pub raw: String,
     /// The line number in the source file.
     pub line_number: u64,
+    /// Whether the line number is estimated, for lines near the end of a
+    /// large file.
+    #[serde(default)]
+    pub line_number_approximate: bool,
     /// Additional context parsed from the log entry.
     #[serde(default)]
     pub context: Option<serde_json::Value>,
+    /// Extra data added by log processors (e.g. Monolog's `extra`).
+    #[serde(default)]
+    pub extra: Option<serde_json::Value>,
     /// Stack trace lines, if present.
     #[serde(default)]
     pub stack_trace: Option<Vec<String>>,
+    /// Structured frames parsed from the stack trace.
+    #[serde(default)]
+    pub stack_frames: Vec<StackFrame>,
+    /// Structured exception, if the entry carries one.
+    #[serde(default)]
+    pub exception: Option<ExceptionInfo>,
     /// The log channel/environment (e.g., "local", "production").
     #[serde(default)]
     pub channel: Option<String>,
+    /// Colour ranges of the raw line, from ANSI escapes stripped before parsing.
+    #[serde(default)]
+    pub styles: Vec<StyleSpan>,
 }
 
 impl LogEntry {
@@ -45,6 +61,11 @@ impl LogEntry {
         stack_trace: Option<Vec<String>>,
         channel: Option<String>,
     ) -> Self {
+        let stack_frames = stack_trace
+            .as_deref()
+            .map(StackFrame::parse_all)
+            .unwrap_or_default();
+
         Self {
             id,
             timestamp,
@@ -52,9 +73,14 @@ impl LogEntry {
             message,
             raw,
             line_number,
+            line_number_approximate: false,
             context,
+            extra: None,
             stack_trace,
+            stack_frames,
+            exception: None,
             channel,
+            styles: Vec::new(),
         }
     }
 
@@ -74,9 +100,14 @@ impl LogEntry {
             message,
             raw,
             line_number,
+            line_number_approximate: false,
             context: None,
+            extra: None,
             stack_trace: None,
+            stack_frames: Vec::new(),
+            exception: None,
             channel: None,
+            styles: Vec::new(),
         }
     }
 
@@ -90,9 +121,14 @@ impl LogEntry {
             message: raw.clone(),
             raw,
             line_number,
+            line_number_approximate: false,
             context: None,
+            extra: None,
             stack_trace: None,
+            stack_frames: Vec::new(),
+            exception: None,
             channel: None,
+            styles: Vec::new(),
         }
     }
 
@@ -107,17 +143,36 @@ impl LogEntry {
         self
     }
 
-    /// Adds a stack trace to this entry.
+    /// Adds extra processor data to this entry.
+    pub fn with_extra(mut self, extra: serde_json::Value) -> Self {
+        self.extra = Some(extra);
+        self
+    }
+
+    /// Adds a stack trace to this entry, parsing its frames.
     pub fn with_stack_trace(mut self, stack_trace: Vec<String>) -> Self {
+        self.stack_frames = StackFrame::parse_all(&stack_trace);
         self.stack_trace = Some(stack_trace);
         self
     }
 
+    /// Adds a structured exception to this entry.
+    pub fn with_exception(mut self, exception: ExceptionInfo) -> Self {
+        self.exception = Some(exception);
+        self
+    }
+
     /// Adds a channel to this entry.
     pub fn with_channel(mut self, channel: String) -> Self {
         self.channel = Some(channel);
         self
     }
+
+    /// Adds colour ranges of the raw line to this entry.
+    pub fn with_styles(mut self, styles: Vec<StyleSpan>) -> Self {
+        self.styles = styles;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -140,4 +195,20 @@ mod tests {
         let entry_with_trace = entry.with_stack_trace(vec!["at file.rs:10".to_string()]);
         assert!(entry_with_trace.has_stack_trace());
     }
+
+    #[test]
+    fn test_stack_frames_from_stack_trace() {
+        let entry = LogEntry::from_raw("Error".to_string(), 1).with_stack_trace(vec![
+            "[stacktrace]".to_string(),
+            "#0 /app/Http/Kernel.php(12): App\\Http\\Kernel->handle()".to_string(),
+            "#1 {main}".to_string(),
+        ]);
+
+        assert_eq!(entry.stack_frames.len(), 2);
+        assert_eq!(
+            entry.stack_frames[0].file.as_deref(),
+            Some("/app/Http/Kernel.php")
+        );
+        assert_eq!(entry.stack_frames[0].line, Some(12));
+    }
 }
@@ -3,7 +3,7 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
-use crate::domain::log_watching::FilePath;
+use crate::domain::log_watching::{FilePath, LevelAliases, SourceTimezone, TextEncoding};
 
 /// The type of log source.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -44,6 +44,31 @@ pub struct LogSource {
     pub created_at: DateTime<Utc>,
     /// Last activity timestamp.
     pub last_activity_at: Option<DateTime<Utc>>,
+    /// Name of the parser pinned to this source.
+    #[serde(default)]
+    pub parser: Option<String>,
+    /// Whether the parser was picked by format detection rather than by the user.
+    #[serde(default)]
+    pub parser_detected: bool,
+    /// Timezone the source writes timestamps in when they have no offset.
+    #[serde(default)]
+    pub timezone: SourceTimezone,
+    /// Whether ANSI escapes are stripped from lines and kept as style ranges.
+    #[serde(default = "default_strip_ansi")]
+    pub strip_ansi: bool,
+    /// The source's own level names and numbers, mapped to levels.
+    #[serde(default)]
+    pub level_aliases: LevelAliases,
+    /// Encoding the source's files are decoded in, unless they start with a byte order mark.
+    #[serde(default)]
+    pub encoding: TextEncoding,
+    /// For folder sources, the matching file currently followed.
+    #[serde(default)]
+    pub current_file: Option<FilePath>,
+}
+
+fn default_strip_ansi() -> bool {
+    true
 }
 
 impl LogSource {
@@ -62,6 +87,13 @@ impl LogSource {
             error_message: None,
             created_at: Utc::now(),
             last_activity_at: None,
+            parser: None,
+            parser_detected: false,
+            timezone: SourceTimezone::default(),
+            strip_ansi: true,
+            level_aliases: LevelAliases::default(),
+            encoding: TextEncoding::default(),
+            current_file: None,
         }
     }
 
@@ -80,6 +112,13 @@ impl LogSource {
             error_message: None,
             created_at: Utc::now(),
             last_activity_at: None,
+            parser: None,
+            parser_detected: false,
+            timezone: SourceTimezone::default(),
+            strip_ansi: true,
+            level_aliases: LevelAliases::default(),
+            encoding: TextEncoding::default(),
+            current_file: None,
         }
     }
 
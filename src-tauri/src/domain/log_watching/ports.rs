@@ -6,6 +6,7 @@ use std::path::{Path, PathBuf};
 
 use super::entities::log_entry::LogEntry;
 use super::entities::log_source::{LogSource, LogSourceStatus};
+use super::value_objects::text_encoding::TextEncoding;
 
 /// Events emitted by the file watcher.
 #[derive(Debug, Clone)]
@@ -13,17 +14,22 @@ pub enum FileWatchEvent {
     /// New content was appended to the file.
     ContentAppended {
         path: PathBuf,
-        content: String,
-        line_number: usize,
+        /// The lines read, without their line endings.
+        lines: Vec<String>,
+        /// Number of the first line in `lines`; the others follow on.
+        first_line_number: usize,
+        /// Whether the line numbers are estimated rather than counted.
+        approximate: bool,
     },
-    /// The file was created.
+    /// The file was created. Sent before any content of a new file in a
+    /// watched folder.
     FileCreated { path: PathBuf },
     /// The file was deleted.
     FileDeleted { path: PathBuf },
-    /// The file was renamed.
-    FileRenamed { from: PathBuf, to: PathBuf },
     /// The file was truncated (size decreased).
     FileTruncated { path: PathBuf },
+    /// A new file replaced the followed one at the path (log rotation).
+    FileRotated { path: PathBuf },
     /// An error occurred while watching.
     Error { path: PathBuf, message: String },
 }
@@ -60,6 +66,9 @@ pub trait FileWatcher: Send {
     /// Start watching a directory for files matching a pattern.
     fn watch_directory(&mut self, path: PathBuf, pattern: &str) -> WatchResult<()>;
 
+    /// Set the encoding a watched file, or a watched folder's files, are decoded in.
+    fn set_encoding(&mut self, path: &Path, encoding: TextEncoding) -> WatchResult<()>;
+
     /// Stop watching a path.
     fn unwatch(&mut self, path: &Path) -> WatchResult<()>;
 
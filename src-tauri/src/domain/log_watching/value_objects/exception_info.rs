@@ -0,0 +1,26 @@
+//! ExceptionInfo value object describing an exception attached to a log entry.
+
+use serde::{Deserialize, Serialize};
+
+use super::StackFrame;
+
+/// An exception decomposed from a log entry.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExceptionInfo {
+    /// Fully qualified exception class.
+    pub class: String,
+    /// Exception code, if any (not always numeric, e.g. SQLSTATE codes).
+    pub code: Option<String>,
+    /// Exception message.
+    pub message: String,
+    /// File the exception was thrown in.
+    pub file: Option<String>,
+    /// Line the exception was thrown at.
+    pub line: Option<u32>,
+    /// Stack frames, innermost first.
+    #[serde(default)]
+    pub frames: Vec<StackFrame>,
+    /// The exception this one was caused by, if any.
+    #[serde(default)]
+    pub previous: Option<Box<ExceptionInfo>>,
+}
@@ -0,0 +1,110 @@
+//! LevelAliases value object mapping a source's own level names to LogLevels.
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use super::LogLevel;
+
+/// Level names or numbers a source uses, mapped to the levels they mean.
+///
+/// Keys are matched case-insensitively; numbers are stored in their decimal
+/// form, so `3` and `"3"` are the same alias.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct LevelAliases(BTreeMap<String, LogLevel>);
+
+impl LevelAliases {
+    /// Creates an empty alias table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn normalize(token: &str) -> String {
+        token.trim().to_lowercase()
+    }
+
+    /// Adds or replaces an alias.
+    pub fn insert(&mut self, token: &str, level: LogLevel) {
+        self.0.insert(Self::normalize(token), level);
+    }
+
+    /// Removes an alias, returning the level it mapped to.
+    pub fn remove(&mut self, token: &str) -> Option<LogLevel> {
+        self.0.remove(&Self::normalize(token))
+    }
+
+    /// Looks up the level an alias maps to.
+    pub fn get(&self, token: &str) -> Option<LogLevel> {
+        if self.0.is_empty() {
+            return None;
+        }
+        self.0.get(&Self::normalize(token)).copied()
+    }
+
+    /// Resolves a level token, preferring an alias over the built-in names.
+    pub fn resolve(&self, token: &str) -> LogLevel {
+        self.get(token).unwrap_or_else(|| LogLevel::parse(token))
+    }
+
+    /// Checks if the table has no aliases.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns the aliases in key order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, LogLevel)> {
+        self.0.iter().map(|(token, level)| (token.as_str(), *level))
+    }
+}
+
+impl<S: AsRef<str>> FromIterator<(S, LogLevel)> for LevelAliases {
+    fn from_iter<I: IntoIterator<Item = (S, LogLevel)>>(iter: I) -> Self {
+        let mut aliases = Self::new();
+        for (token, level) in iter {
+            aliases.insert(token.as_ref(), level);
+        }
+        aliases
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aliases_are_case_insensitive() {
+        let aliases: LevelAliases = [("FINE", LogLevel::Debug), ("Severe", LogLevel::Error)]
+            .into_iter()
+            .collect();
+
+        assert_eq!(aliases.get("fine"), Some(LogLevel::Debug));
+        assert_eq!(aliases.get(" SEVERE "), Some(LogLevel::Error));
+        assert_eq!(aliases.get("info"), None);
+    }
+
+    #[test]
+    fn test_resolve_falls_back_to_builtin_names() {
+        let mut aliases = LevelAliases::new();
+        aliases.insert("3", LogLevel::Error);
+        aliases.insert("warn", LogLevel::Error);
+
+        assert_eq!(aliases.resolve("3"), LogLevel::Error);
+        assert_eq!(aliases.resolve("WARN"), LogLevel::Error);
+        assert_eq!(aliases.resolve("debug"), LogLevel::Debug);
+        assert_eq!(aliases.resolve("unknown"), LogLevel::Info);
+
+        assert_eq!(aliases.remove("WARN"), Some(LogLevel::Error));
+        assert_eq!(aliases.resolve("warn"), LogLevel::Warning);
+    }
+
+    #[test]
+    fn test_serializes_as_map() {
+        let aliases: LevelAliases = [("PANIC", LogLevel::Emergency)].into_iter().collect();
+
+        let json = serde_json::to_string(&aliases).unwrap();
+        assert_eq!(json, r#"{"panic":"emergency"}"#);
+
+        let parsed: LevelAliases = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, aliases);
+    }
+}
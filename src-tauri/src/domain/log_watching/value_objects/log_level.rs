@@ -8,15 +8,16 @@ use serde::{Deserialize, Serialize};
 )]
 #[serde(rename_all = "lowercase")]
 pub enum LogLevel {
-    Debug = 0,
+    Trace = 0,
+    Debug = 1,
     #[default]
-    Info = 1,
-    Notice = 2,
-    Warning = 3,
-    Error = 4,
-    Critical = 5,
-    Alert = 6,
-    Emergency = 7,
+    Info = 2,
+    Notice = 3,
+    Warning = 4,
+    Error = 5,
+    Critical = 6,
+    Alert = 7,
+    Emergency = 8,
 }
 
 impl LogLevel {
@@ -37,6 +38,7 @@ impl LogLevel {
     /// ```
     pub fn parse(s: &str) -> Self {
         match s.to_lowercase().trim() {
+            "trace" => LogLevel::Trace,
             "debug" => LogLevel::Debug,
             "info" | "information" => LogLevel::Info,
             "notice" => LogLevel::Notice,
@@ -62,6 +64,7 @@ impl LogLevel {
     /// Returns the CSS class for this log level.
     pub fn css_class(&self) -> &'static str {
         match self {
+            LogLevel::Trace => "log-level-trace",
             LogLevel::Debug => "log-level-debug",
             LogLevel::Info => "log-level-info",
             LogLevel::Notice => "log-level-notice",
@@ -77,6 +80,7 @@ impl LogLevel {
 impl std::fmt::Display for LogLevel {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let s = match self {
+            LogLevel::Trace => "TRACE",
             LogLevel::Debug => "DEBUG",
             LogLevel::Info => "INFO",
             LogLevel::Notice => "NOTICE",
@@ -94,6 +98,12 @@ impl std::fmt::Display for LogLevel {
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_parse_trace() {
+        assert_eq!(LogLevel::parse("TRACE"), LogLevel::Trace);
+        assert_eq!(LogLevel::parse("trace"), LogLevel::Trace);
+    }
+
     #[test]
     fn test_parse_debug() {
         assert_eq!(LogLevel::parse("DEBUG"), LogLevel::Debug);
@@ -138,14 +148,15 @@ mod tests {
 
     #[test]
     fn test_severity() {
-        assert_eq!(LogLevel::Debug.severity(), 0);
-        assert_eq!(LogLevel::Info.severity(), 1);
-        assert_eq!(LogLevel::Notice.severity(), 2);
-        assert_eq!(LogLevel::Warning.severity(), 3);
-        assert_eq!(LogLevel::Error.severity(), 4);
-        assert_eq!(LogLevel::Critical.severity(), 5);
-        assert_eq!(LogLevel::Alert.severity(), 6);
-        assert_eq!(LogLevel::Emergency.severity(), 7);
+        assert_eq!(LogLevel::Trace.severity(), 0);
+        assert_eq!(LogLevel::Debug.severity(), 1);
+        assert_eq!(LogLevel::Info.severity(), 2);
+        assert_eq!(LogLevel::Notice.severity(), 3);
+        assert_eq!(LogLevel::Warning.severity(), 4);
+        assert_eq!(LogLevel::Error.severity(), 5);
+        assert_eq!(LogLevel::Critical.severity(), 6);
+        assert_eq!(LogLevel::Alert.severity(), 7);
+        assert_eq!(LogLevel::Emergency.severity(), 8);
     }
 
     #[test]
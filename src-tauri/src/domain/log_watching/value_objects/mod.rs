@@ -1,7 +1,19 @@
 //! Value objects for the log watching context.
 
+pub mod exception_info;
 pub mod file_path;
+pub mod level_aliases;
 pub mod log_level;
+pub mod source_timezone;
+pub mod stack_frame;
+pub mod text_encoding;
+pub mod text_style;
 
+pub use exception_info::ExceptionInfo;
 pub use file_path::FilePath;
+pub use level_aliases::LevelAliases;
 pub use log_level::LogLevel;
+pub use source_timezone::SourceTimezone;
+pub use stack_frame::StackFrame;
+pub use text_encoding::TextEncoding;
+pub use text_style::{AnsiColor, StyleSpan, TextStyle};
@@ -0,0 +1,206 @@
+//! SourceTimezone value object describing how a source writes its timestamps.
+
+use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, Offset, TimeZone, Utc};
+use chrono_tz::Tz;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// The timezone used to interpret timestamps written without an offset.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SourceTimezone {
+    /// The system's local timezone.
+    #[default]
+    Local,
+    /// An IANA timezone such as `Europe/Oslo`.
+    Named(Tz),
+    /// A fixed offset from UTC such as `+02:00`.
+    Fixed(FixedOffset),
+}
+
+// Custom serialization to serialize as a plain string
+impl Serialize for SourceTimezone {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for SourceTimezone {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl SourceTimezone {
+    /// Coordinated Universal Time.
+    pub const UTC: Self = Self::Named(Tz::UTC);
+
+    /// Converts a wall-clock time in this timezone to UTC.
+    ///
+    /// Ambiguous times (when clocks go back) resolve to the earlier instant.
+    /// Times skipped when clocks go forward use the offset in effect at the
+    /// same instant read as UTC.
+    pub fn to_utc(&self, naive: NaiveDateTime) -> DateTime<Utc> {
+        match self {
+            Self::Local => Self::resolve(&Local, naive),
+            Self::Named(tz) => Self::resolve(tz, naive),
+            Self::Fixed(offset) => Self::resolve(offset, naive),
+        }
+    }
+
+    fn resolve<T: TimeZone>(tz: &T, naive: NaiveDateTime) -> DateTime<Utc> {
+        match tz.from_local_datetime(&naive).earliest() {
+            Some(dt) => dt.with_timezone(&Utc),
+            None => (naive - tz.offset_from_utc_datetime(&naive).fix()).and_utc(),
+        }
+    }
+
+    /// Parses a fixed offset such as `+02:00`, `+0200` or `-05`.
+    fn parse_offset(s: &str) -> Option<FixedOffset> {
+        let sign = match s.as_bytes().first()? {
+            b'+' => 1,
+            b'-' => -1,
+            _ => return None,
+        };
+        let digits: String = s[1..].chars().filter(|c| *c != ':').collect();
+        if !digits.chars().all(|c| c.is_ascii_digit()) {
+            return None;
+        }
+        let (hours, minutes) = match digits.len() {
+            2 => (digits.parse::<i32>().ok()?, 0),
+            4 => (
+                digits[..2].parse::<i32>().ok()?,
+                digits[2..].parse::<i32>().ok()?,
+            ),
+            _ => return None,
+        };
+        if minutes >= 60 {
+            return None;
+        }
+        FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60))
+    }
+}
+
+impl FromStr for SourceTimezone {
+    type Err = String;
+
+    /// Parses `local`, `UTC`, an IANA name or a fixed offset.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("local") {
+            return Ok(Self::Local);
+        }
+        if s.eq_ignore_ascii_case("utc") || s == "Z" {
+            return Ok(Self::UTC);
+        }
+        if let Some(offset) = Self::parse_offset(s) {
+            return Ok(Self::Fixed(offset));
+        }
+        s.parse::<Tz>()
+            .map(Self::Named)
+            .map_err(|_| format!("Unknown timezone: {}", s))
+    }
+}
+
+impl fmt::Display for SourceTimezone {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Local => write!(f, "local"),
+            Self::Named(tz) => write!(f, "{}", tz.name()),
+            Self::Fixed(offset) => write!(f, "{}", offset),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn naive(s: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S").unwrap()
+    }
+
+    #[test]
+    fn test_parse_and_display() {
+        let test_cases = [
+            ("local", "local"),
+            ("Local", "local"),
+            ("UTC", "UTC"),
+            ("utc", "UTC"),
+            ("Europe/Oslo", "Europe/Oslo"),
+            ("+02:00", "+02:00"),
+            ("-0530", "-05:30"),
+            ("+01", "+01:00"),
+        ];
+
+        for (input, expected) in test_cases {
+            let timezone: SourceTimezone = input
+                .parse()
+                .unwrap_or_else(|e| panic!("Failed for: {}: {}", input, e));
+            assert_eq!(timezone.to_string(), expected, "Failed for: {}", input);
+        }
+
+        assert!("Mars/Olympus_Mons".parse::<SourceTimezone>().is_err());
+        assert!("+25:00".parse::<SourceTimezone>().is_err());
+        assert!("+02:75".parse::<SourceTimezone>().is_err());
+    }
+
+    #[test]
+    fn test_to_utc_named_follows_dst() {
+        let oslo: SourceTimezone = "Europe/Oslo".parse().unwrap();
+
+        assert_eq!(
+            oslo.to_utc(naive("2024-01-15 10:30:00")).to_rfc3339(),
+            "2024-01-15T09:30:00+00:00"
+        );
+        assert_eq!(
+            oslo.to_utc(naive("2024-07-15 10:30:00")).to_rfc3339(),
+            "2024-07-15T08:30:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_to_utc_dst_edges() {
+        let oslo: SourceTimezone = "Europe/Oslo".parse().unwrap();
+
+        // Clocks go back at 03:00 CEST: 02:30 happens twice
+        assert_eq!(
+            oslo.to_utc(naive("2024-10-27 02:30:00")).to_rfc3339(),
+            "2024-10-27T00:30:00+00:00"
+        );
+        // Clocks go forward at 02:00 CET: 02:30 never happens
+        assert_eq!(
+            oslo.to_utc(naive("2024-03-31 02:30:00")).to_rfc3339(),
+            "2024-03-31T00:30:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_to_utc_fixed_offset() {
+        let timezone: SourceTimezone = "-05:00".parse().unwrap();
+
+        assert_eq!(
+            timezone.to_utc(naive("2024-01-15 10:30:00")).to_rfc3339(),
+            "2024-01-15T15:30:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_serde_round_trip() {
+        let timezone: SourceTimezone = "America/New_York".parse().unwrap();
+        let json = serde_json::to_string(&timezone).unwrap();
+
+        assert_eq!(json, "\"America/New_York\"");
+        assert_eq!(
+            serde_json::from_str::<SourceTimezone>(&json).unwrap(),
+            timezone
+        );
+    }
+}
@@ -0,0 +1,415 @@
+//! StackFrame value object representing one frame of a stack trace.
+//!
+//! Frames are parsed from the stack trace lines of PHP, Python, JVM, Node.js
+//! and Rust backtraces, so the frontend can open a frame in the IDE without
+//! scanning the raw lines itself.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::sync::LazyLock;
+
+/// PHP frame: `#0 /path/File.php(123): Class->method()` or `#1 [internal function]: ...`.
+static PHP_FRAME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*#\d+ (?:(?P<file>.+?)\((?P<line>\d+)\)|(?P<internal>\[internal function\])): (?P<call>.+)$|^\s*#\d+ (?P<main>\{main\})\s*$").unwrap()
+});
+
+/// PHP call: `Class->method(args)`, `Class::method(args)` or `function(args)`.
+static PHP_CALL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(?:(?P<class>[\w\\]+)(?:->|::))?(?P<function>[^(]+)\(").unwrap()
+});
+
+/// Python frame: `File "/app/main.py", line 10, in handler`.
+static PYTHON_FRAME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#"^\s*File "(?P<file>[^"]+)", line (?P<line>\d+)(?:, in (?P<function>.+?))?\s*$"#)
+        .unwrap()
+});
+
+/// JVM frame: `at com.example.Service.method(Service.java:42)`, with an optional module prefix.
+static JVM_FRAME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\s*at (?:[\w.$-]+(?:@[\w.-]+)?/{1,2})?(?P<qualified>[\w$.<>]+)\((?P<location>[^)]*)\)\s*(?:~?\[.*\])?\s*$",
+    )
+    .unwrap()
+});
+
+/// Node.js frame: `at Object.<anonymous> (/app/index.js:10:5)` or `at /app/index.js:10:5`.
+static NODE_FRAME_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\s*at (?:(?P<function>.+?) \()?(?P<file>[^()\s][^()]*?):(?P<line>\d+):\d+\)?\s*$")
+        .unwrap()
+});
+
+/// Rust backtrace function line: `  12: myapp::handlers::run`.
+static RUST_FUNCTION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*\d+: (?P<function>\S.*?)(?:::h[0-9a-f]{16})?\s*$").unwrap());
+
+/// Rust backtrace location line: `at ./src/main.rs:4:5`.
+static RUST_LOCATION_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\s*at (?P<file>[^()\s]+?):(?P<line>\d+)(?::\d+)?\s*$").unwrap());
+
+/// Path fragments that mark a frame as vendor or library code.
+const VENDOR_PATHS: &[&str] = &[
+    "/vendor/",
+    "/node_modules/",
+    "site-packages/",
+    "dist-packages/",
+    "/lib/python",
+    "/rustc/",
+    "/.cargo/registry/",
+    "/.rustup/",
+    "node:",
+    "<frozen ",
+];
+
+/// Class and function prefixes that mark a frame as vendor or library code.
+const VENDOR_PREFIXES: &[&str] = &[
+    "java.",
+    "javax.",
+    "jdk.",
+    "sun.",
+    "com.sun.",
+    "kotlin.",
+    "kotlinx.",
+    "scala.",
+    "org.springframework.",
+    "org.apache.",
+    "org.hibernate.",
+    "io.netty.",
+    "Illuminate\\",
+    "Symfony\\",
+    "std::",
+    "core::",
+    "alloc::",
+    "tokio::",
+    "__rust",
+];
+
+/// A single frame of a stack trace.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StackFrame {
+    /// Source file, if known.
+    pub file: Option<String>,
+    /// Line number in the file, if known.
+    pub line: Option<u32>,
+    /// Function or method name.
+    pub function: Option<String>,
+    /// Class, namespace or module containing the function.
+    pub class: Option<String>,
+    /// Whether the frame is in vendor, library or runtime code.
+    pub is_vendor: bool,
+}
+
+impl StackFrame {
+    /// Parses a single stack trace line.
+    ///
+    /// Returns None if the line is not a recognised frame. Rust backtraces
+    /// put the location on a separate line; use [`StackFrame::parse_all`]
+    /// to combine them.
+    pub fn parse(line: &str) -> Option<Self> {
+        Self::parse_php(line)
+            .or_else(|| Self::parse_python(line))
+            .or_else(|| Self::parse_jvm(line))
+            .or_else(|| Self::parse_node(line))
+            .or_else(|| Self::parse_rust_function(line))
+            .map(Self::flag_vendor)
+    }
+
+    /// Parses all frames from stack trace lines, skipping lines that are not frames.
+    pub fn parse_all(lines: &[String]) -> Vec<Self> {
+        let mut frames: Vec<Self> = Vec::new();
+        let mut rust_pending = false;
+
+        for line in lines {
+            if rust_pending {
+                if let Some(captures) = RUST_LOCATION_REGEX.captures(line) {
+                    if let Some(frame) = frames.last_mut() {
+                        frame.file = Some(captures["file"].to_string());
+                        frame.line = captures["line"].parse().ok();
+                        *frame = std::mem::take(frame).flag_vendor();
+                    }
+                    rust_pending = false;
+                    continue;
+                }
+            }
+
+            rust_pending = false;
+            if let Some(frame) = Self::parse_rust_function(line) {
+                frames.push(frame.flag_vendor());
+                rust_pending = !RUST_LOCATION_REGEX.is_match(line);
+            } else if let Some(frame) = Self::parse(line) {
+                frames.push(frame);
+            }
+        }
+
+        frames
+    }
+
+    fn parse_php(line: &str) -> Option<Self> {
+        let captures = PHP_FRAME_REGEX.captures(line)?;
+        if captures.name("main").is_some() {
+            return Some(Self {
+                function: Some("{main}".to_string()),
+                ..Self::default()
+            });
+        }
+
+        let call = captures.name("call")?.as_str();
+        let (class, function) = match PHP_CALL_REGEX.captures(call) {
+            Some(c) => (
+                c.name("class").map(|m| m.as_str().to_string()),
+                Some(c["function"].to_string()),
+            ),
+            None => (None, Some(call.to_string())),
+        };
+
+        Some(Self {
+            file: captures.name("file").map(|m| m.as_str().to_string()),
+            line: captures.name("line").and_then(|m| m.as_str().parse().ok()),
+            function,
+            class,
+            is_vendor: captures.name("internal").is_some(),
+        })
+    }
+
+    fn parse_python(line: &str) -> Option<Self> {
+        let captures = PYTHON_FRAME_REGEX.captures(line)?;
+        Some(Self {
+            file: Some(captures["file"].to_string()),
+            line: captures["line"].parse().ok(),
+            function: captures.name("function").map(|m| m.as_str().to_string()),
+            ..Self::default()
+        })
+    }
+
+    fn parse_jvm(line: &str) -> Option<Self> {
+        let captures = JVM_FRAME_REGEX.captures(line)?;
+        let (class, function) = match captures["qualified"].rsplit_once('.') {
+            Some((class, function)) => (Some(class.to_string()), function.to_string()),
+            None => (None, captures["qualified"].to_string()),
+        };
+        let (file, line) = match captures["location"].rsplit_once(':') {
+            Some((file, line)) => (Some(file.to_string()), line.parse().ok()),
+            None => (None, None),
+        };
+
+        Some(Self {
+            file,
+            line,
+            function: Some(function),
+            class,
+            is_vendor: false,
+        })
+    }
+
+    fn parse_node(line: &str) -> Option<Self> {
+        let captures = NODE_FRAME_REGEX.captures(line)?;
+        let file = captures["file"].trim();
+        let file = file.strip_prefix("file://").unwrap_or(file);
+
+        let (class, function) = match captures.name("function").map(|m| {
+            let name = m.as_str();
+            let name = name.strip_prefix("async ").unwrap_or(name);
+            name.strip_prefix("new ").unwrap_or(name)
+        }) {
+            Some(name) => match name.split_once(' ') {
+                // e.g. "Object.method [as alias]"
+                Some((name, _)) => Self::split_node_name(name),
+                None => Self::split_node_name(name),
+            },
+            None => (None, None),
+        };
+
+        Some(Self {
+            file: Some(file.to_string()),
+            line: captures["line"].parse().ok(),
+            function,
+            class,
+            is_vendor: false,
+        })
+    }
+
+    fn split_node_name(name: &str) -> (Option<String>, Option<String>) {
+        match name.rsplit_once('.') {
+            Some((class, function)) => (Some(class.to_string()), Some(function.to_string())),
+            None => (None, Some(name.to_string())),
+        }
+    }
+
+    fn parse_rust_function(line: &str) -> Option<Self> {
+        let captures = RUST_FUNCTION_REGEX.captures(line)?;
+        let path = &captures["function"];
+        let (class, function) = match path.rsplit_once("::") {
+            Some((module, function)) if !path.starts_with('<') => {
+                (Some(module.to_string()), function.to_string())
+            }
+            _ => (None, path.to_string()),
+        };
+
+        Some(Self {
+            function: Some(function),
+            class,
+            ..Self::default()
+        })
+    }
+
+    /// Flag the frame as vendor code based on its file or class.
+    fn flag_vendor(mut self) -> Self {
+        let in_vendor_path = self
+            .file
+            .as_deref()
+            .is_some_and(|file| VENDOR_PATHS.iter().any(|p| file.contains(p)));
+        let in_vendor_class = self
+            .class
+            .as_deref()
+            .or(self.function.as_deref())
+            .is_some_and(|name| VENDOR_PREFIXES.iter().any(|p| name.starts_with(p)));
+
+        self.is_vendor |= in_vendor_path || in_vendor_class;
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn lines(lines: &[&str]) -> Vec<String> {
+        lines.iter().map(|l| l.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_php_frames() {
+        let frame = StackFrame::parse(
+            "#0 /var/www/app/Http/Controllers/UserController.php(42): App\\Services\\UserService->find(123)",
+        )
+        .expect("Should parse frame");
+
+        assert_eq!(
+            frame.file.as_deref(),
+            Some("/var/www/app/Http/Controllers/UserController.php")
+        );
+        assert_eq!(frame.line, Some(42));
+        assert_eq!(frame.class.as_deref(), Some("App\\Services\\UserService"));
+        assert_eq!(frame.function.as_deref(), Some("find"));
+        assert!(!frame.is_vendor);
+
+        let vendor = StackFrame::parse(
+            "#1 /var/www/vendor/laravel/framework/src/Illuminate/Pipeline/Pipeline.php(183): Illuminate\\Pipeline\\Pipeline->Illuminate\\Pipeline\\{closure}(Object(Illuminate\\Http\\Request))",
+        )
+        .expect("Should parse frame");
+        assert!(vendor.is_vendor);
+        assert_eq!(
+            vendor.function.as_deref(),
+            Some("Illuminate\\Pipeline\\{closure}")
+        );
+
+        let internal = StackFrame::parse("#2 [internal function]: App\\Jobs\\Sync->handle()")
+            .expect("Should parse frame");
+        assert!(internal.file.is_none());
+        assert!(internal.is_vendor);
+
+        let main = StackFrame::parse("#3 {main}").expect("Should parse frame");
+        assert_eq!(main.function.as_deref(), Some("{main}"));
+    }
+
+    #[test]
+    fn test_parse_python_frames() {
+        let frame = StackFrame::parse(r#"  File "/app/main.py", line 10, in handler"#)
+            .expect("Should parse frame");
+
+        assert_eq!(frame.file.as_deref(), Some("/app/main.py"));
+        assert_eq!(frame.line, Some(10));
+        assert_eq!(frame.function.as_deref(), Some("handler"));
+        assert!(!frame.is_vendor);
+
+        let vendor = StackFrame::parse(
+            r#"  File "/usr/lib/python3.11/site-packages/requests/api.py", line 59, in request"#,
+        )
+        .expect("Should parse frame");
+        assert!(vendor.is_vendor);
+    }
+
+    #[test]
+    fn test_parse_jvm_frames() {
+        let frame =
+            StackFrame::parse("\tat com.example.orders.OrderService.place(OrderService.java:42)")
+                .expect("Should parse frame");
+
+        assert_eq!(frame.file.as_deref(), Some("OrderService.java"));
+        assert_eq!(frame.line, Some(42));
+        assert_eq!(
+            frame.class.as_deref(),
+            Some("com.example.orders.OrderService")
+        );
+        assert_eq!(frame.function.as_deref(), Some("place"));
+        assert!(!frame.is_vendor);
+
+        let vendor = StackFrame::parse("\tat java.base/java.lang.Thread.run(Thread.java:829)")
+            .expect("Should parse frame");
+        assert_eq!(vendor.class.as_deref(), Some("java.lang.Thread"));
+        assert!(vendor.is_vendor);
+
+        let native = StackFrame::parse("\tat sun.misc.Unsafe.park(Native Method)")
+            .expect("Should parse frame");
+        assert!(native.file.is_none());
+        assert!(native.line.is_none());
+    }
+
+    #[test]
+    fn test_parse_node_frames() {
+        let frame = StackFrame::parse("    at async UserService.find (/app/src/users.js:10:5)")
+            .expect("Should parse frame");
+
+        assert_eq!(frame.file.as_deref(), Some("/app/src/users.js"));
+        assert_eq!(frame.line, Some(10));
+        assert_eq!(frame.class.as_deref(), Some("UserService"));
+        assert_eq!(frame.function.as_deref(), Some("find"));
+
+        let anonymous = StackFrame::parse("    at /app/index.js:3:1").expect("Should parse frame");
+        assert_eq!(anonymous.file.as_deref(), Some("/app/index.js"));
+        assert!(anonymous.function.is_none());
+
+        let vendor = StackFrame::parse(
+            "    at Layer.handle (/app/node_modules/express/lib/router/layer.js:95:5)",
+        )
+        .expect("Should parse frame");
+        assert!(vendor.is_vendor);
+
+        let internal = StackFrame::parse(
+            "    at process.processTicksAndRejections (node:internal/process/task_queues:95:5)",
+        )
+        .expect("Should parse frame");
+        assert!(internal.is_vendor);
+    }
+
+    #[test]
+    fn test_parse_all_rust_backtrace() {
+        let trace = lines(&[
+            "stack backtrace:",
+            "   0: std::panicking::begin_panic",
+            "             at /rustc/90b35a6239c3d8bdabc530a6a0816f7ff89a0aaf/library/std/src/panicking.rs:616:12",
+            "   1: myapp::handlers::run",
+            "             at ./src/handlers.rs:4:5",
+            "   2: myapp::main::{{closure}}",
+        ]);
+
+        let frames = StackFrame::parse_all(&trace);
+
+        assert_eq!(frames.len(), 3);
+        assert!(frames[0].is_vendor);
+        assert_eq!(frames[1].class.as_deref(), Some("myapp::handlers"));
+        assert_eq!(frames[1].function.as_deref(), Some("run"));
+        assert_eq!(frames[1].file.as_deref(), Some("./src/handlers.rs"));
+        assert_eq!(frames[1].line, Some(4));
+        assert!(!frames[1].is_vendor);
+        assert_eq!(frames[2].function.as_deref(), Some("{{closure}}"));
+        assert!(frames[2].file.is_none());
+    }
+
+    #[test]
+    fn test_non_frames() {
+        assert!(StackFrame::parse("Traceback (most recent call last):").is_none());
+        assert!(StackFrame::parse("java.lang.IllegalStateException: boom").is_none());
+        assert!(StackFrame::parse("[stacktrace]").is_none());
+        assert!(StackFrame::parse("").is_none());
+    }
+}
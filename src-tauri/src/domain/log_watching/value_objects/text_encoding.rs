@@ -0,0 +1,234 @@
+//! TextEncoding value object describing how a source's bytes are decoded.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::fmt;
+use std::str::FromStr;
+
+/// Characters for bytes 0x80 to 0x9F in Windows-1252.
+///
+/// The five bytes the code page leaves undefined map to the C1 control
+/// with the same value, as browsers decode them.
+const WINDOWS_1252_HIGH: [char; 32] = [
+    '\u{20AC}', '\u{0081}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}', '\u{2021}',
+    '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{008D}', '\u{017D}', '\u{008F}',
+    '\u{0090}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}', '\u{2013}', '\u{2014}',
+    '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}', '\u{009D}', '\u{017E}', '\u{0178}',
+];
+
+/// The character encoding a source writes its lines in.
+///
+/// Decoding never fails: bytes that are not valid in the encoding become
+/// U+FFFD. A byte order mark at the start of a file takes precedence over
+/// the configured encoding, see [`TextEncoding::from_bom`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum TextEncoding {
+    /// UTF-8.
+    #[default]
+    Utf8,
+    /// ISO-8859-1, one byte per character.
+    Latin1,
+    /// Windows-1252, Latin-1 with printable characters in 0x80 to 0x9F.
+    Windows1252,
+    /// UTF-16, little-endian.
+    Utf16Le,
+    /// UTF-16, big-endian.
+    Utf16Be,
+}
+
+// Custom serialization to serialize as a plain string
+impl Serialize for TextEncoding {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for TextEncoding {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+impl TextEncoding {
+    /// Detects the encoding from a byte order mark at the start of a file,
+    /// returning it with the length of the mark.
+    pub fn from_bom(bytes: &[u8]) -> Option<(Self, usize)> {
+        match bytes {
+            [0xEF, 0xBB, 0xBF, ..] => Some((Self::Utf8, 3)),
+            [0xFF, 0xFE, ..] => Some((Self::Utf16Le, 2)),
+            [0xFE, 0xFF, ..] => Some((Self::Utf16Be, 2)),
+            _ => None,
+        }
+    }
+
+    /// Returns the number of bytes in each code unit.
+    pub fn unit_len(&self) -> usize {
+        match self {
+            Self::Utf16Le | Self::Utf16Be => 2,
+            _ => 1,
+        }
+    }
+
+    /// Returns the bytes that end a line.
+    pub fn newline(&self) -> &'static [u8] {
+        match self {
+            Self::Utf16Le => b"\n\0",
+            Self::Utf16Be => b"\0\n",
+            _ => b"\n",
+        }
+    }
+
+    /// Checks if bytes holding one line end with its line ending.
+    pub fn ends_line(&self, bytes: &[u8]) -> bool {
+        bytes.len() % self.unit_len() == 0 && bytes.ends_with(self.newline())
+    }
+
+    /// Decodes bytes, replacing invalid sequences with U+FFFD.
+    pub fn decode(&self, bytes: &[u8]) -> String {
+        match self {
+            Self::Utf8 => String::from_utf8_lossy(bytes).into_owned(),
+            Self::Latin1 => bytes.iter().map(|&b| char::from(b)).collect(),
+            Self::Windows1252 => bytes
+                .iter()
+                .map(|&b| match b {
+                    0x80..=0x9F => WINDOWS_1252_HIGH[usize::from(b - 0x80)],
+                    _ => char::from(b),
+                })
+                .collect(),
+            Self::Utf16Le => Self::decode_utf16(bytes, u16::from_le_bytes),
+            Self::Utf16Be => Self::decode_utf16(bytes, u16::from_be_bytes),
+        }
+    }
+
+    fn decode_utf16(bytes: &[u8], unit: fn([u8; 2]) -> u16) -> String {
+        let chunks = bytes.chunks_exact(2);
+        let odd_byte = !chunks.remainder().is_empty();
+        let mut text: String = char::decode_utf16(chunks.map(|pair| unit([pair[0], pair[1]])))
+            .map(|c| c.unwrap_or(char::REPLACEMENT_CHARACTER))
+            .collect();
+        if odd_byte {
+            text.push(char::REPLACEMENT_CHARACTER);
+        }
+        text
+    }
+
+    /// Decodes one line, without its line ending or a leading byte order mark.
+    pub fn decode_line(&self, bytes: &[u8]) -> String {
+        let text = self.decode(bytes);
+        let text = text.trim_end_matches(['\r', '\n']);
+        text.strip_prefix('\u{FEFF}').unwrap_or(text).to_string()
+    }
+}
+
+impl FromStr for TextEncoding {
+    type Err = String;
+
+    /// Parses an encoding name such as `utf-8`, `latin-1` or `windows-1252`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().replace('_', "-").as_str() {
+            "utf-8" | "utf8" => Ok(Self::Utf8),
+            "latin-1" | "latin1" | "iso-8859-1" => Ok(Self::Latin1),
+            "windows-1252" | "cp1252" => Ok(Self::Windows1252),
+            "utf-16le" | "utf16le" => Ok(Self::Utf16Le),
+            "utf-16be" | "utf16be" => Ok(Self::Utf16Be),
+            other => Err(format!("Unknown encoding: {}", other)),
+        }
+    }
+}
+
+impl fmt::Display for TextEncoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Utf8 => "utf-8",
+            Self::Latin1 => "latin-1",
+            Self::Windows1252 => "windows-1252",
+            Self::Utf16Le => "utf-16le",
+            Self::Utf16Be => "utf-16be",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_and_display() {
+        let cases = [
+            ("utf-8", TextEncoding::Utf8),
+            ("UTF8", TextEncoding::Utf8),
+            ("ISO-8859-1", TextEncoding::Latin1),
+            ("cp1252", TextEncoding::Windows1252),
+            ("utf_16le", TextEncoding::Utf16Le),
+            ("UTF-16BE", TextEncoding::Utf16Be),
+        ];
+
+        for (name, expected) in cases {
+            assert_eq!(
+                name.parse::<TextEncoding>(),
+                Ok(expected),
+                "Failed for: {}",
+                name
+            );
+        }
+        assert_eq!(TextEncoding::Windows1252.to_string(), "windows-1252");
+        assert!("ebcdic".parse::<TextEncoding>().is_err());
+    }
+
+    #[test]
+    fn test_decode_is_lossy() {
+        assert_eq!(TextEncoding::Utf8.decode(b"caf\xE9 ok"), "caf\u{FFFD} ok");
+        assert_eq!(TextEncoding::Latin1.decode(b"caf\xE9"), "café");
+        assert_eq!(
+            TextEncoding::Windows1252.decode(b"\x80 \x93quoted\x94 \x81"),
+            "€ \u{201C}quoted\u{201D} \u{81}"
+        );
+    }
+
+    #[test]
+    fn test_decode_utf16() {
+        let le: Vec<u8> = "Zoë 😀\n"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+        let be: Vec<u8> = "Zoë 😀\n"
+            .encode_utf16()
+            .flat_map(u16::to_be_bytes)
+            .collect();
+
+        assert_eq!(TextEncoding::Utf16Le.decode_line(&le), "Zoë 😀");
+        assert_eq!(TextEncoding::Utf16Be.decode_line(&be), "Zoë 😀");
+        assert!(TextEncoding::Utf16Le.ends_line(&le));
+        assert!(!TextEncoding::Utf16Le.ends_line(&le[1..]));
+        // A lone surrogate and an odd trailing byte are replaced
+        assert_eq!(
+            TextEncoding::Utf16Le.decode(&[0x3D, 0xD8, 0x41, 0x00, 0x42]),
+            "\u{FFFD}A\u{FFFD}"
+        );
+    }
+
+    #[test]
+    fn test_from_bom() {
+        assert_eq!(
+            TextEncoding::from_bom(b"\xEF\xBB\xBFhello"),
+            Some((TextEncoding::Utf8, 3))
+        );
+        assert_eq!(
+            TextEncoding::from_bom(b"\xFF\xFEh\0"),
+            Some((TextEncoding::Utf16Le, 2))
+        );
+        assert_eq!(
+            TextEncoding::from_bom(b"\xFE\xFF\0h"),
+            Some((TextEncoding::Utf16Be, 2))
+        );
+        assert_eq!(TextEncoding::from_bom(b"hello"), None);
+        assert_eq!(TextEncoding::Utf16Le.decode_line(b"\xFF\xFEh\0"), "h");
+    }
+}
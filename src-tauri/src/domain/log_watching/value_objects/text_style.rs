@@ -0,0 +1,53 @@
+//! Text style value objects describing the colours of a log line.
+//!
+//! Styles come from the ANSI escape sequences stripped from a line, so the
+//! viewer can render the original colours on the clean text.
+
+use serde::{Deserialize, Serialize};
+
+/// A terminal colour.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum AnsiColor {
+    /// Palette index: 0-7 standard, 8-15 bright, 16-255 extended.
+    Indexed(u8),
+    /// 24-bit colour as `[r, g, b]`.
+    Rgb([u8; 3]),
+}
+
+/// The style applied to a run of text.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TextStyle {
+    #[serde(default)]
+    pub foreground: Option<AnsiColor>,
+    #[serde(default)]
+    pub background: Option<AnsiColor>,
+    #[serde(default)]
+    pub bold: bool,
+    #[serde(default)]
+    pub dim: bool,
+    #[serde(default)]
+    pub italic: bool,
+    #[serde(default)]
+    pub underline: bool,
+    #[serde(default)]
+    pub inverse: bool,
+}
+
+impl TextStyle {
+    /// Checks if this is the terminal's default style.
+    pub fn is_plain(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A styled range of a line, in character offsets.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StyleSpan {
+    /// Offset of the first styled character.
+    pub start: usize,
+    /// Offset just past the last styled character.
+    pub end: usize,
+    /// The style of the range.
+    pub style: TextStyle,
+}
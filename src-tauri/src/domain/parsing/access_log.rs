@@ -0,0 +1,270 @@
+//! Apache/Nginx access log parser.
+//!
+//! Parses the Common and Combined log formats:
+//! 127.0.0.1 - frank [15/Jan/2024:10:30:00 +0000] "GET /index.php HTTP/1.1" 200 2326 "http://example.com/" "Mozilla/5.0"
+//!
+//! Any numeric field after the user agent (nginx `$request_time`, Apache `%D`)
+//! is recorded as the request time.
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::sync::LazyLock;
+
+use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::log_level::LogLevel;
+
+use super::{LogParser, ParseContext};
+
+/// Regex for parsing Common and Combined access log lines.
+static ACCESS_LOG_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r#"^(\S+) (\S+) (\S+) \[([^\]]+)\] "((?:[^"\\]|\\.)*)" (\d{3}) (\d+|-)(?: "((?:[^"\\]|\\.)*)" "((?:[^"\\]|\\.)*)")?(?:\s+(.*?))?\s*$"#,
+    )
+    .unwrap()
+});
+
+/// Apache/Nginx access log parser.
+#[derive(Debug, Default, Clone)]
+pub struct AccessLogParser;
+
+impl AccessLogParser {
+    /// Create a new access log parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse the timestamp from an access log line.
+    fn parse_timestamp(&self, timestamp_str: &str) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_str(timestamp_str, "%d/%b/%Y:%H:%M:%S %z")
+            .ok()
+            .map(|dt| dt.with_timezone(&Utc))
+    }
+
+    /// Derive the log level from the HTTP status code.
+    fn level_for_status(status: u16) -> LogLevel {
+        match status {
+            500.. => LogLevel::Error,
+            400..=499 => LogLevel::Warning,
+            _ => LogLevel::Info,
+        }
+    }
+
+    /// Find the request time among the trailing fields, if any.
+    fn parse_request_time(&self, trailing: &str) -> Option<f64> {
+        trailing
+            .split_whitespace()
+            .rev()
+            .find_map(|token| token.trim_matches('"').parse::<f64>().ok())
+    }
+
+    /// Convert a "-" placeholder to None.
+    fn non_empty(value: &str) -> Option<&str> {
+        (value != "-" && !value.is_empty()).then_some(value)
+    }
+}
+
+impl LogParser for AccessLogParser {
+    fn name(&self) -> &'static str {
+        "Access Log"
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let captures = ACCESS_LOG_REGEX.captures(line)?;
+
+        let client_ip = captures.get(1)?.as_str();
+        let remote_user = captures.get(3)?.as_str();
+        let timestamp_str = captures.get(4)?.as_str();
+        let request = captures.get(5)?.as_str();
+        let status: u16 = captures.get(6)?.as_str().parse().ok()?;
+        let bytes = captures.get(7)?.as_str().parse::<u64>().ok();
+        let referer = captures.get(8).and_then(|m| Self::non_empty(m.as_str()));
+        let user_agent = captures.get(9).and_then(|m| Self::non_empty(m.as_str()));
+        let request_time = captures
+            .get(10)
+            .and_then(|m| self.parse_request_time(m.as_str()));
+
+        let mut request_parts = request.split_whitespace();
+        let (method, path, protocol) = match (
+            request_parts.next(),
+            request_parts.next(),
+            request_parts.next(),
+        ) {
+            (Some(method), Some(path), protocol) => (Some(method), Some(path), protocol),
+            _ => (None, None, None),
+        };
+
+        let message = match (method, path) {
+            (Some(method), Some(path)) => format!("{} {} {}", method, path, status),
+            _ => format!("{} {}", request, status),
+        };
+
+        let mut context = Map::new();
+        context.insert("client_ip".to_string(), json!(client_ip));
+        if let Some(user) = Self::non_empty(remote_user) {
+            context.insert("remote_user".to_string(), json!(user));
+        }
+        if let Some(method) = method {
+            context.insert("method".to_string(), json!(method));
+        }
+        if let Some(path) = path {
+            context.insert("path".to_string(), json!(path));
+        }
+        if let Some(protocol) = protocol {
+            context.insert("protocol".to_string(), json!(protocol));
+        }
+        context.insert("status".to_string(), json!(status));
+        context.insert("bytes".to_string(), json!(bytes));
+        if let Some(referer) = referer {
+            context.insert("referer".to_string(), json!(referer));
+        }
+        if let Some(user_agent) = user_agent {
+            context.insert("user_agent".to_string(), json!(user_agent));
+        }
+        if let Some(request_time) = request_time {
+            context.insert("request_time".to_string(), json!(request_time));
+        }
+
+        Some(LogEntry::new(
+            format!("access-{}", line_number),
+            self.parse_timestamp(timestamp_str),
+            parse_context
+                .level_alias(&status.to_string())
+                .unwrap_or_else(|| Self::level_for_status(status)),
+            message,
+            line.to_string(),
+            line_number,
+            Some(Value::Object(context)),
+            None,
+            None,
+        ))
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        ACCESS_LOG_REGEX.is_match(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_common_format() {
+        let parser = AccessLogParser::new();
+        let line =
+            r#"127.0.0.1 - frank [15/Jan/2024:10:30:00 +0100] "GET /index.php HTTP/1.1" 200 2326"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Info);
+        assert_eq!(entry.message, "GET /index.php 200");
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T09:30:00+00:00"
+        );
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["client_ip"], "127.0.0.1");
+        assert_eq!(context["remote_user"], "frank");
+        assert_eq!(context["method"], "GET");
+        assert_eq!(context["path"], "/index.php");
+        assert_eq!(context["status"], 200);
+        assert_eq!(context["bytes"], 2326);
+        assert!(context.get("user_agent").is_none());
+    }
+
+    #[test]
+    fn test_parse_combined_format_with_request_time() {
+        let parser = AccessLogParser::new();
+        let line = r#"10.0.0.5 - - [15/Jan/2024:10:30:00 +0000] "POST /api/orders HTTP/2.0" 502 0 "https://example.com/cart" "Mozilla/5.0 (X11; Linux x86_64)" 1.234"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["referer"], "https://example.com/cart");
+        assert_eq!(context["user_agent"], "Mozilla/5.0 (X11; Linux x86_64)");
+        assert_eq!(context["request_time"], 1.234);
+        assert!(context.get("remote_user").is_none());
+    }
+
+    #[test]
+    fn test_levels_from_status() {
+        let test_cases = [
+            (200, LogLevel::Info),
+            (304, LogLevel::Info),
+            (404, LogLevel::Warning),
+            (499, LogLevel::Warning),
+            (500, LogLevel::Error),
+            (503, LogLevel::Error),
+        ];
+
+        for (status, expected_level) in test_cases {
+            assert_eq!(
+                AccessLogParser::level_for_status(status),
+                expected_level,
+                "Failed for: {}",
+                status
+            );
+        }
+    }
+
+    #[test]
+    fn test_level_aliases_for_status() {
+        let parser = AccessLogParser::new();
+        let parse_context = ParseContext {
+            level_aliases: [("404", LogLevel::Info)].into_iter().collect(),
+            ..ParseContext::utc()
+        };
+        let line = |status| {
+            format!(
+                r#"127.0.0.1 - - [15/Jan/2024:10:30:00 +0000] "GET /x HTTP/1.1" {} 0"#,
+                status
+            )
+        };
+
+        let entry = parser
+            .parse_with(&line(404), 1, &parse_context)
+            .expect("Should parse log line");
+        assert_eq!(entry.level, LogLevel::Info);
+
+        let entry = parser
+            .parse_with(&line(403), 2, &parse_context)
+            .expect("Should parse log line");
+        assert_eq!(entry.level, LogLevel::Warning);
+    }
+
+    #[test]
+    fn test_parse_malformed_request_and_missing_bytes() {
+        let parser = AccessLogParser::new();
+        let line = r#"192.168.1.1 - - [15/Jan/2024:10:30:00 +0000] "-" 400 - "-" "-""#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Warning);
+        assert_eq!(entry.message, "- 400");
+
+        let context = entry.context.expect("Should have context");
+        assert!(context["bytes"].is_null());
+        assert!(context.get("method").is_none());
+        assert!(context.get("referer").is_none());
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let parser = AccessLogParser::new();
+
+        assert!(parser.can_parse(
+            r#"::1 - - [15/Jan/2024:10:30:00 +0000] "GET / HTTP/1.1" 200 612 "-" "curl/8.0""#
+        ));
+        assert!(!parser.can_parse("[2024-01-15 10:30:00] local.ERROR: Test"));
+        assert!(!parser.can_parse("Plain text log"));
+    }
+}
@@ -0,0 +1,246 @@
+//! ANSI/VT100 escape sequence handling.
+//!
+//! Dev servers and test runners colour their output with escape sequences,
+//! which would otherwise end up in messages and break the parser regexes.
+//! Sequences are stripped before parsing; SGR colour and attribute changes
+//! are kept as style ranges over the clean text.
+
+use crate::domain::log_watching::{AnsiColor, StyleSpan, TextStyle};
+
+/// The escape character.
+const ESC: char = '\x1b';
+
+/// The single-character control sequence introducer.
+const CSI: char = '\u{9b}';
+
+/// Strips ANSI escape sequences from log lines.
+#[derive(Debug, Default, Clone)]
+pub struct AnsiStripper;
+
+impl AnsiStripper {
+    /// Create a new ANSI stripper.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Checks if the line contains any escape sequence.
+    pub fn has_escapes(&self, line: &str) -> bool {
+        line.contains([ESC, CSI])
+    }
+
+    /// Remove escape sequences from the line.
+    ///
+    /// Returns the clean text and its styled ranges, in character offsets.
+    pub fn strip(&self, line: &str) -> (String, Vec<StyleSpan>) {
+        let mut text = String::with_capacity(line.len());
+        let mut spans = Vec::new();
+        let mut style = TextStyle::default();
+        let mut run_start = 0;
+        let mut length = 0;
+        let mut chars = line.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            let params = match c {
+                ESC => match chars.next() {
+                    Some('[') => Self::read_csi(&mut chars),
+                    Some(']' | 'P' | 'X' | '^' | '_') => {
+                        Self::skip_string(&mut chars);
+                        None
+                    }
+                    Some(' '..='/') => {
+                        // nF sequences: intermediates, then a final byte
+                        while chars.next_if(|c| matches!(c, ' '..='/')).is_some() {}
+                        chars.next();
+                        None
+                    }
+                    _ => None,
+                },
+                CSI => Self::read_csi(&mut chars),
+                other => {
+                    text.push(other);
+                    length += 1;
+                    continue;
+                }
+            };
+
+            let Some(params) = params else {
+                continue;
+            };
+            let next = Self::apply_sgr(style, &params);
+            if next != style {
+                Self::push_span(&mut spans, run_start, length, style);
+                style = next;
+                run_start = length;
+            }
+        }
+        Self::push_span(&mut spans, run_start, length, style);
+
+        (text, spans)
+    }
+
+    /// Read a CSI sequence after its introducer.
+    ///
+    /// Returns the parameters if it is an SGR (`m`) sequence.
+    fn read_csi(chars: &mut impl Iterator<Item = char>) -> Option<String> {
+        let mut params = String::new();
+        for c in chars {
+            match c {
+                '@'..='~' => return (c == 'm').then_some(params),
+                other => params.push(other),
+            }
+        }
+        None
+    }
+
+    /// Skip an OSC, DCS or similar string, terminated by BEL or ST.
+    fn skip_string(chars: &mut std::iter::Peekable<impl Iterator<Item = char>>) {
+        while let Some(c) = chars.next() {
+            match c {
+                '\x07' | '\u{9c}' => return,
+                ESC if chars.next_if_eq(&'\\').is_some() => return,
+                _ => {}
+            }
+        }
+    }
+
+    /// Close a run of text, merging it with the previous span if they touch.
+    fn push_span(spans: &mut Vec<StyleSpan>, start: usize, end: usize, style: TextStyle) {
+        if start == end || style.is_plain() {
+            return;
+        }
+        match spans.last_mut() {
+            Some(last) if last.end == start && last.style == style => last.end = end,
+            _ => spans.push(StyleSpan { start, end, style }),
+        }
+    }
+
+    /// Apply SGR parameters to a style.
+    fn apply_sgr(mut style: TextStyle, params: &str) -> TextStyle {
+        let codes: Vec<u16> = params
+            .split([';', ':'])
+            .map(|code| code.parse().unwrap_or(0))
+            .collect();
+        let mut codes = codes.into_iter();
+
+        while let Some(code) = codes.next() {
+            match code {
+                0 => style = TextStyle::default(),
+                1 => style.bold = true,
+                2 => style.dim = true,
+                3 => style.italic = true,
+                4 => style.underline = true,
+                7 => style.inverse = true,
+                22 => {
+                    style.bold = false;
+                    style.dim = false;
+                }
+                23 => style.italic = false,
+                24 => style.underline = false,
+                27 => style.inverse = false,
+                30..=37 => style.foreground = Some(AnsiColor::Indexed((code - 30) as u8)),
+                38 => style.foreground = Self::extended_color(&mut codes),
+                39 => style.foreground = None,
+                40..=47 => style.background = Some(AnsiColor::Indexed((code - 40) as u8)),
+                48 => style.background = Self::extended_color(&mut codes),
+                49 => style.background = None,
+                90..=97 => style.foreground = Some(AnsiColor::Indexed((code - 90 + 8) as u8)),
+                100..=107 => style.background = Some(AnsiColor::Indexed((code - 100 + 8) as u8)),
+                _ => {}
+            }
+        }
+
+        style
+    }
+
+    /// Read a `5;n` palette or `2;r;g;b` colour following code 38 or 48.
+    fn extended_color(codes: &mut impl Iterator<Item = u16>) -> Option<AnsiColor> {
+        let channel = |value: Option<u16>| value.map(|v| v.min(255) as u8);
+        match codes.next()? {
+            5 => channel(codes.next()).map(AnsiColor::Indexed),
+            2 => Some(AnsiColor::Rgb([
+                channel(codes.next())?,
+                channel(codes.next())?,
+                channel(codes.next())?,
+            ])),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_strip_plain_text_is_unchanged() {
+        let stripper = AnsiStripper::new();
+
+        let (text, spans) = stripper.strip("plain line");
+
+        assert_eq!(text, "plain line");
+        assert!(spans.is_empty());
+        assert!(!stripper.has_escapes("plain line"));
+    }
+
+    #[test]
+    fn test_strip_keeps_colour_spans() {
+        let stripper = AnsiStripper::new();
+        let line = "\x1b[2m10:30:00\x1b[0m \x1b[1;31mERROR\x1b[0m Payment failed";
+
+        let (text, spans) = stripper.strip(line);
+
+        assert_eq!(text, "10:30:00 ERROR Payment failed");
+        assert_eq!(spans.len(), 2);
+        assert_eq!((spans[0].start, spans[0].end), (0, 8));
+        assert!(spans[0].style.dim);
+        assert_eq!((spans[1].start, spans[1].end), (9, 14));
+        assert!(spans[1].style.bold);
+        assert_eq!(spans[1].style.foreground, Some(AnsiColor::Indexed(1)));
+    }
+
+    #[test]
+    fn test_extended_colours() {
+        let stripper = AnsiStripper::new();
+        let test_cases = [
+            ("\x1b[38;5;208mwarn\x1b[m", Some(AnsiColor::Indexed(208))),
+            (
+                "\x1b[38;2;255;128;0mwarn\x1b[39m",
+                Some(AnsiColor::Rgb([255, 128, 0])),
+            ),
+            ("\x1b[93mwarn\x1b[0m", Some(AnsiColor::Indexed(11))),
+        ];
+
+        for (line, expected) in test_cases {
+            let (text, spans) = stripper.strip(line);
+            assert_eq!(text, "warn", "Failed for: {:?}", line);
+            assert_eq!(spans.len(), 1, "Failed for: {:?}", line);
+            assert_eq!(
+                spans[0].style.foreground, expected,
+                "Failed for: {:?}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_strips_non_colour_sequences() {
+        let stripper = AnsiStripper::new();
+        let line = "\x1b]0;title\x07\x1b[2K\x1b[1G\x1b(Bdone ✓ \x1b[32mok\x1b[0m";
+
+        let (text, spans) = stripper.strip(line);
+
+        assert_eq!(text, "done ✓ ok");
+        assert_eq!(spans.len(), 1);
+        assert_eq!((spans[0].start, spans[0].end), (7, 9));
+    }
+
+    #[test]
+    fn test_adjacent_runs_with_same_style_merge() {
+        let stripper = AnsiStripper::new();
+
+        let (_, spans) = stripper.strip("\x1b[31mab\x1b[31mcd\x1b[0m");
+
+        assert_eq!(spans.len(), 1);
+        assert_eq!((spans[0].start, spans[0].end), (0, 4));
+    }
+}
@@ -0,0 +1,224 @@
+//! Apache error log parser.
+//!
+//! Parses the Apache 2.4 error log format, including module-prefixed levels:
+//! [Mon Jan 15 10:30:00.123456 2024] [php:error] [pid 1234:tid 5678] [client 127.0.0.1:5678] Message
+//!
+//! The older Apache 2.2 format without module and pid is also supported:
+//! [Mon Jan 15 10:30:00 2024] [error] [client 127.0.0.1] Message
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::sync::LazyLock;
+
+use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::log_level::LogLevel;
+use crate::domain::log_watching::SourceTimezone;
+
+use super::{LogParser, ParseContext};
+
+/// Regex for parsing Apache error log lines.
+static APACHE_ERROR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\[(\w{3} \w{3} +\d{1,2} \d{2}:\d{2}:\d{2}(?:\.\d+)? \d{4})\] \[(?:([\w-]+):)?(\w+)\](?: \[pid (\d+)(?::tid (\d+))?\])?(?: \[client ([^\]]+)\])? ?(.*)$",
+    )
+    .unwrap()
+});
+
+/// Regex for the Apache error code prefix (e.g. "AH00112:").
+static ERROR_CODE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^(AH\d{5}):\s*").unwrap());
+
+/// Regex for the trailing referer Apache appends to client errors.
+static REFERER_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r", referer: (\S+)$").unwrap());
+
+/// Apache error log parser.
+#[derive(Debug, Default, Clone)]
+pub struct ApacheErrorLogParser;
+
+impl ApacheErrorLogParser {
+    /// Create a new Apache error log parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse the timestamp from an Apache error log line.
+    fn parse_timestamp(
+        &self,
+        timestamp_str: &str,
+        timezone: &SourceTimezone,
+    ) -> Option<DateTime<Utc>> {
+        let normalized = timestamp_str
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+        NaiveDateTime::parse_from_str(&normalized, "%a %b %d %H:%M:%S%.f %Y")
+            .ok()
+            .map(|dt| timezone.to_utc(dt))
+    }
+
+    /// Parse an Apache level, mapping the trace1-trace8 levels to trace.
+    fn parse_level(&self, level_str: &str, parse_context: &ParseContext) -> LogLevel {
+        if let Some(level) = parse_context.level_alias(level_str) {
+            level
+        } else if level_str.to_lowercase().starts_with("trace") {
+            LogLevel::Trace
+        } else {
+            LogLevel::parse(level_str)
+        }
+    }
+}
+
+impl LogParser for ApacheErrorLogParser {
+    fn name(&self) -> &'static str {
+        "Apache Error"
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let captures = APACHE_ERROR_REGEX.captures(line)?;
+
+        let timestamp_str = captures.get(1)?.as_str();
+        let module = captures.get(2).map(|m| m.as_str().to_string());
+        let level_str = captures.get(3)?.as_str();
+        let pid = captures.get(4).and_then(|m| m.as_str().parse::<u32>().ok());
+        let tid = captures.get(5).and_then(|m| m.as_str().parse::<u64>().ok());
+        let client = captures.get(6).map(|m| m.as_str());
+        let mut message = captures.get(7)?.as_str();
+
+        let mut context = Map::new();
+        if let Some(pid) = pid {
+            context.insert("pid".to_string(), json!(pid));
+        }
+        if let Some(tid) = tid {
+            context.insert("tid".to_string(), json!(tid));
+        }
+        if let Some(client) = client {
+            context.insert("client".to_string(), json!(client));
+        }
+        if let Some(code) = ERROR_CODE_REGEX.captures(message) {
+            context.insert("error_code".to_string(), json!(&code[1]));
+            message = &message[code.get(0)?.end()..];
+        }
+        if let Some(referer) = REFERER_REGEX.captures(message) {
+            context.insert("referer".to_string(), json!(&referer[1]));
+            message = &message[..referer.get(0)?.start()];
+        }
+
+        let context = if context.is_empty() {
+            None
+        } else {
+            Some(Value::Object(context))
+        };
+
+        Some(LogEntry::new(
+            format!("apache-error-{}", line_number),
+            self.parse_timestamp(timestamp_str, &parse_context.timezone),
+            self.parse_level(level_str, parse_context),
+            message.to_string(),
+            line.to_string(),
+            line_number,
+            context,
+            None,
+            module,
+        ))
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        APACHE_ERROR_REGEX.is_match(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_apache24_line() {
+        let parser = ApacheErrorLogParser::new();
+        let line = "[Mon Jan 15 10:30:00.123456 2024] [php:error] [pid 1234:tid 5678] [client 127.0.0.1:51234] PHP Fatal error:  Uncaught Exception, referer: http://localhost/";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.channel.as_deref(), Some("php"));
+        assert_eq!(entry.message, "PHP Fatal error:  Uncaught Exception");
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T10:30:00.123456+00:00"
+        );
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["pid"], 1234);
+        assert_eq!(context["tid"], 5678);
+        assert_eq!(context["client"], "127.0.0.1:51234");
+        assert_eq!(context["referer"], "http://localhost/");
+    }
+
+    #[test]
+    fn test_parse_module_prefixed_levels() {
+        let parser = ApacheErrorLogParser::new();
+
+        let test_cases = [
+            ("core:warn", "core", LogLevel::Warning),
+            ("php7:error", "php7", LogLevel::Error),
+            ("mpm_event:notice", "mpm_event", LogLevel::Notice),
+            ("ssl:trace3", "ssl", LogLevel::Trace),
+            ("authz_core:crit", "authz_core", LogLevel::Critical),
+        ];
+
+        for (level, module, expected_level) in test_cases {
+            let line = format!("[Mon Jan 15 10:30:00 2024] [{}] [pid 1] message", level);
+            let entry = parser.parse(&line, 1).expect("Should parse log line");
+            assert_eq!(entry.level, expected_level, "Failed for: {}", line);
+            assert_eq!(
+                entry.channel.as_deref(),
+                Some(module),
+                "Failed for: {}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_error_code() {
+        let parser = ApacheErrorLogParser::new();
+        let line = "[Mon Jan 15 10:30:00.000001 2024] [core:notice] [pid 42] AH00094: Command line: '/usr/sbin/apache2'";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.message, "Command line: '/usr/sbin/apache2'");
+        assert_eq!(
+            entry.context.expect("Should have context")["error_code"],
+            "AH00094"
+        );
+    }
+
+    #[test]
+    fn test_parse_apache22_line() {
+        let parser = ApacheErrorLogParser::new();
+        let line = "[Mon Jan  8 10:30:00 2024] [error] [client 10.0.0.1] File does not exist: /var/www/robots.txt";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert!(entry.channel.is_none());
+        assert_eq!(entry.message, "File does not exist: /var/www/robots.txt");
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-08T10:30:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let parser = ApacheErrorLogParser::new();
+
+        assert!(parser.can_parse("[Mon Jan 15 10:30:00 2024] [core:warn] [pid 1] warning"));
+        assert!(!parser.can_parse("[2024-01-15 10:30:00] local.ERROR: Test"));
+        assert!(!parser.can_parse("Plain text log"));
+    }
+}
@@ -0,0 +1,489 @@
+//! Container runtime log parsers.
+//!
+//! Unwraps the per-line envelopes written by container runtimes and hands
+//! the inner payload to the other parsers, so an application's own format
+//! is still recognised inside a container log:
+//! - Docker json-file: {"log":"message\n","stream":"stderr","time":"2024-01-15T10:30:00.1Z"}
+//! - Kubernetes CRI: 2024-01-15T10:30:00.1Z stdout F message
+//!
+//! Payloads split over several lines (CRI `P` tags, Docker records without
+//! a trailing newline) are reassembled before parsing. The stream is
+//! recorded in the context.
+//!
+//! The payload parsers are shared through [`PayloadParsers`], so the owner
+//! of the parser chain can keep them in step with its own.
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::sync::{Arc, LazyLock, RwLock};
+
+use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::SourceTimezone;
+
+use super::{timestamp, FallbackParser, LogParser, ParseContext};
+
+/// Regex for CRI log lines: timestamp, stream, tag and content.
+static CRI_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^(\S+) (stdout|stderr) ([PF]) ?(.*)$").unwrap());
+
+/// Maximum number of payloads gathered for one multi-line entry.
+const MAX_PAYLOADS: usize = 1000;
+
+/// The parsers container payloads are handed to, in the order they are tried.
+///
+/// Clones share the same list, so replacing it with [`PayloadParsers::set`]
+/// applies to every container parser created with a clone.
+#[derive(Clone, Default)]
+pub struct PayloadParsers(Arc<RwLock<Vec<Arc<dyn LogParser>>>>);
+
+impl PayloadParsers {
+    /// Create a shared list of payload parsers.
+    pub fn new(parsers: Vec<Arc<dyn LogParser>>) -> Self {
+        Self(Arc::new(RwLock::new(parsers)))
+    }
+
+    /// Replace the payload parsers.
+    pub fn set(&self, parsers: Vec<Arc<dyn LogParser>>) {
+        *self.0.write().unwrap() = parsers;
+    }
+}
+
+/// A single container log line, unwrapped.
+struct ContainerRecord {
+    content: String,
+    stream: String,
+    timestamp: Option<DateTime<Utc>>,
+    /// Whether the content continues on the next record.
+    partial: bool,
+}
+
+/// A payload reassembled from one or more container log lines.
+struct Payload {
+    text: String,
+    stream: String,
+    timestamp: Option<DateTime<Utc>>,
+    /// Number of container log lines the payload was assembled from.
+    lines: usize,
+}
+
+/// Shared unwrapping logic for the container log parsers.
+struct Unwrapper<'a> {
+    inner: &'a [Arc<dyn LogParser>],
+    record: fn(&str) -> Option<ContainerRecord>,
+}
+
+impl Unwrapper<'_> {
+    /// Reassemble the payload starting at the first line.
+    fn payload(&self, lines: &[&str]) -> Option<Payload> {
+        let first = (self.record)(lines.first()?)?;
+        let mut payload = Payload {
+            text: first.content,
+            stream: first.stream,
+            timestamp: first.timestamp,
+            lines: 1,
+        };
+
+        let mut partial = first.partial;
+        while partial {
+            let Some(next) = lines
+                .get(payload.lines)
+                .and_then(|line| (self.record)(line))
+            else {
+                break;
+            };
+            payload.text.push_str(&next.content);
+            payload.lines += 1;
+            partial = next.partial;
+        }
+
+        Some(payload)
+    }
+
+    /// Parse the entry starting at the first line, returning it with the number of lines consumed.
+    ///
+    /// The entry keeps the first container log line as its raw text, so it
+    /// can be parsed again later.
+    fn parse(
+        &self,
+        lines: &[&str],
+        start_line: u64,
+        parse_context: &ParseContext,
+    ) -> Option<(LogEntry, usize)> {
+        let first = self.payload(lines)?;
+        let parser = self.inner.iter().find(|p| p.can_parse(&first.text));
+
+        let Some(parser) = parser else {
+            let consumed = first.lines;
+            let entry = FallbackParser::new().parse(&first.text, start_line, parse_context);
+            return Some((Self::decorate(entry, &first, lines[0]), consumed));
+        };
+
+        // Gather following payloads until the inner parser sees a new entry
+        let mut payloads = vec![first];
+        let mut offset = payloads[0].lines;
+        while payloads.len() < MAX_PAYLOADS && offset < lines.len() {
+            let Some(next) = self.payload(&lines[offset..]) else {
+                break;
+            };
+            if parser.can_parse(&next.text) {
+                break;
+            }
+            offset += next.lines;
+            payloads.push(next);
+        }
+
+        let texts: Vec<&str> = payloads.iter().map(|p| p.text.as_str()).collect();
+        let (entry, used) = match parser.parse_multiline_with(&texts, start_line, parse_context) {
+            Some((entry, used)) => (entry, used.clamp(1, payloads.len())),
+            None => (
+                FallbackParser::new().parse(texts[0], start_line, parse_context),
+                1,
+            ),
+        };
+        let consumed = payloads[..used].iter().map(|p| p.lines).sum();
+
+        Some((Self::decorate(entry, &payloads[0], lines[0]), consumed))
+    }
+
+    /// Add the container stream and timestamp to an inner entry.
+    fn decorate(mut entry: LogEntry, payload: &Payload, raw: &str) -> LogEntry {
+        entry.raw = raw.to_string();
+        if entry.timestamp.is_none() {
+            entry.timestamp = payload.timestamp;
+        }
+
+        match entry.context.as_mut() {
+            Some(Value::Object(context)) => {
+                context.insert("stream".to_string(), json!(payload.stream));
+            }
+            Some(_) => {}
+            None => {
+                let mut context = Map::new();
+                context.insert("stream".to_string(), json!(payload.stream));
+                entry.context = Some(Value::Object(context));
+            }
+        }
+
+        entry
+    }
+}
+
+/// Docker json-file log parser.
+pub struct DockerJsonLogParser {
+    inner: PayloadParsers,
+}
+
+impl DockerJsonLogParser {
+    /// The parser's name.
+    pub const NAME: &'static str = "Docker";
+
+    /// Create a new Docker log parser that hands payloads to the given parsers.
+    pub fn new(inner: PayloadParsers) -> Self {
+        Self { inner }
+    }
+
+    /// Unwrap a Docker json-file line.
+    fn record(line: &str) -> Option<ContainerRecord> {
+        let trimmed = line.trim();
+        if !trimmed.starts_with('{') || !trimmed.contains("\"log\"") {
+            return None;
+        }
+        let Value::Object(object) = serde_json::from_str(trimmed).ok()? else {
+            return None;
+        };
+
+        let log = object.get("log")?.as_str()?;
+        let stream = object.get("stream")?.as_str()?;
+        let content = log.strip_suffix('\n').unwrap_or(log);
+
+        Some(ContainerRecord {
+            content: content.strip_suffix('\r').unwrap_or(content).to_string(),
+            stream: stream.to_string(),
+            timestamp: object
+                .get("time")
+                .and_then(|t| t.as_str())
+                .and_then(|t| timestamp::parse_iso8601(t, &SourceTimezone::UTC)),
+            partial: !log.ends_with('\n'),
+        })
+    }
+
+    fn unwrapper<'a>(&self, inner: &'a [Arc<dyn LogParser>]) -> Unwrapper<'a> {
+        Unwrapper {
+            inner,
+            record: Self::record,
+        }
+    }
+}
+
+impl LogParser for DockerJsonLogParser {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let inner = self.inner.0.read().unwrap();
+        self.unwrapper(&inner)
+            .parse(&[line], line_number, parse_context)
+            .map(|(entry, _)| entry)
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        Self::record(line).is_some()
+    }
+
+    fn parse_multiline_with(
+        &self,
+        lines: &[&str],
+        start_line: u64,
+        parse_context: &ParseContext,
+    ) -> Option<(LogEntry, usize)> {
+        let inner = self.inner.0.read().unwrap();
+        self.unwrapper(&inner)
+            .parse(lines, start_line, parse_context)
+    }
+
+    fn continues_on_next_line(&self, line: &str) -> bool {
+        Self::record(line).is_some_and(|record| record.partial)
+    }
+}
+
+/// Kubernetes CRI log parser.
+pub struct CriLogParser {
+    inner: PayloadParsers,
+}
+
+impl CriLogParser {
+    /// The parser's name.
+    pub const NAME: &'static str = "Kubernetes CRI";
+
+    /// Create a new CRI log parser that hands payloads to the given parsers.
+    pub fn new(inner: PayloadParsers) -> Self {
+        Self { inner }
+    }
+
+    /// Unwrap a CRI line.
+    fn record(line: &str) -> Option<ContainerRecord> {
+        let captures = CRI_REGEX.captures(line)?;
+        // Only accept lines that start with a valid timestamp
+        let timestamp = Some(timestamp::parse_iso8601(
+            captures.get(1)?.as_str(),
+            &SourceTimezone::UTC,
+        )?);
+
+        Some(ContainerRecord {
+            content: captures.get(4)?.as_str().to_string(),
+            stream: captures.get(2)?.as_str().to_string(),
+            timestamp,
+            partial: captures.get(3)?.as_str() == "P",
+        })
+    }
+
+    fn unwrapper<'a>(&self, inner: &'a [Arc<dyn LogParser>]) -> Unwrapper<'a> {
+        Unwrapper {
+            inner,
+            record: Self::record,
+        }
+    }
+}
+
+impl LogParser for CriLogParser {
+    fn name(&self) -> &'static str {
+        Self::NAME
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let inner = self.inner.0.read().unwrap();
+        self.unwrapper(&inner)
+            .parse(&[line], line_number, parse_context)
+            .map(|(entry, _)| entry)
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        Self::record(line).is_some()
+    }
+
+    fn parse_multiline_with(
+        &self,
+        lines: &[&str],
+        start_line: u64,
+        parse_context: &ParseContext,
+    ) -> Option<(LogEntry, usize)> {
+        let inner = self.inner.0.read().unwrap();
+        self.unwrapper(&inner)
+            .parse(lines, start_line, parse_context)
+    }
+
+    fn continues_on_next_line(&self, line: &str) -> bool {
+        Self::record(line).is_some_and(|record| record.partial)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::log_watching::log_level::LogLevel;
+    use crate::domain::parsing::{JsonLinesParser, LaravelLogParser};
+
+    fn inner() -> PayloadParsers {
+        PayloadParsers::new(vec![
+            Arc::new(LaravelLogParser::new()),
+            Arc::new(JsonLinesParser::new()),
+        ])
+    }
+
+    #[test]
+    fn test_docker_unwraps_laravel_line() {
+        let parser = DockerJsonLogParser::new(inner());
+        let line = r#"{"log":"[2024-01-15 10:30:00] production.ERROR: Payment failed\n","stream":"stderr","time":"2024-01-15T10:30:00.123456789Z"}"#;
+
+        let entry = parser.parse(line, 7).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.channel.as_deref(), Some("production"));
+        assert_eq!(entry.message, "Payment failed");
+        assert_eq!(entry.line_number, 7);
+        assert_eq!(entry.raw, line);
+        assert_eq!(
+            entry.context.expect("Should have context")["stream"],
+            "stderr"
+        );
+    }
+
+    #[test]
+    fn test_docker_plain_payload_uses_container_time() {
+        let parser = DockerJsonLogParser::new(inner());
+        let line =
+            r#"{"log":"Listening on :8080\n","stream":"stdout","time":"2024-01-15T10:30:00Z"}"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.message, "Listening on :8080");
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T10:30:00+00:00"
+        );
+        assert_eq!(
+            entry.context.expect("Should have context")["stream"],
+            "stdout"
+        );
+    }
+
+    #[test]
+    fn test_docker_reassembles_split_records_and_stack_traces() {
+        let parser = DockerJsonLogParser::new(inner());
+        let lines = [
+            r#"{"log":"[2024-01-15 10:30:00] local.ERROR: Very ","stream":"stderr","time":"2024-01-15T10:30:00Z"}"#,
+            r#"{"log":"long message\n","stream":"stderr","time":"2024-01-15T10:30:00Z"}"#,
+            r#"{"log":"[stacktrace]\n","stream":"stderr","time":"2024-01-15T10:30:00Z"}"#,
+            r##"{"log":"#0 {main}\n","stream":"stderr","time":"2024-01-15T10:30:00Z"}"##,
+            r#"{"log":"[2024-01-15 10:30:01] local.INFO: Next\n","stream":"stdout","time":"2024-01-15T10:30:01Z"}"#,
+        ];
+
+        let (entry, consumed) = parser.parse_multiline(&lines, 1).expect("Should parse");
+
+        assert_eq!(consumed, 4);
+        assert_eq!(entry.message, "Very long message");
+        assert!(entry.has_stack_trace());
+    }
+
+    #[test]
+    fn test_inner_parser_uses_source_timezone() {
+        let parser = DockerJsonLogParser::new(inner());
+        let parse_context = ParseContext {
+            timezone: "Europe/Oslo".parse().unwrap(),
+            ..ParseContext::default()
+        };
+        let line = r#"{"log":"[2024-01-15 11:30:00] local.INFO: Local time\n","stream":"stdout","time":"2024-01-15T10:30:00Z"}"#;
+
+        let entry = parser
+            .parse_with(line, 1, &parse_context)
+            .expect("Should parse log line");
+
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T10:30:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_cri_reassembles_partial_lines() {
+        let parser = CriLogParser::new(inner());
+        let lines = [
+            r#"2024-01-15T10:30:00.1Z stdout P {"level":"warn","#,
+            r#"2024-01-15T10:30:00.1Z stdout F "msg":"Slow query"}"#,
+            "2024-01-15T10:30:01.2Z stderr F plain text",
+        ];
+
+        let (entry, consumed) = parser.parse_multiline(&lines, 1).expect("Should parse");
+
+        assert_eq!(consumed, 2);
+        assert_eq!(entry.level, LogLevel::Warning);
+        assert_eq!(entry.message, "Slow query");
+        assert_eq!(
+            entry.context.expect("Should have context")["stream"],
+            "stdout"
+        );
+
+        let (entry, consumed) = parser
+            .parse_multiline(&lines[2..], 3)
+            .expect("Should parse");
+        assert_eq!(consumed, 1);
+        assert_eq!(entry.message, "plain text");
+        assert_eq!(entry.line_number, 3);
+    }
+
+    #[test]
+    fn test_continues_on_next_line() {
+        let docker = DockerJsonLogParser::new(inner());
+        let cri = CriLogParser::new(inner());
+
+        assert!(cri.continues_on_next_line("2024-01-15T10:30:00.1Z stdout P {\"level\":"));
+        assert!(!cri.continues_on_next_line("2024-01-15T10:30:00.1Z stdout F done"));
+        assert!(docker.continues_on_next_line(
+            r#"{"log":"Very ","stream":"stderr","time":"2024-01-15T10:30:00Z"}"#
+        ));
+        assert!(!docker.continues_on_next_line(
+            r#"{"log":"done\n","stream":"stderr","time":"2024-01-15T10:30:00Z"}"#
+        ));
+    }
+
+    #[test]
+    fn test_payload_parsers_can_be_replaced() {
+        let payload_parsers = PayloadParsers::default();
+        let parser = CriLogParser::new(payload_parsers.clone());
+        let line = r#"2024-01-15T10:30:00.1Z stdout F {"level":"error","msg":"Boom"}"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+        assert_eq!(entry.message, r#"{"level":"error","msg":"Boom"}"#);
+
+        payload_parsers.set(vec![Arc::new(JsonLinesParser::new())]);
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "Boom");
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let docker = DockerJsonLogParser::new(inner());
+        let cri = CriLogParser::new(inner());
+
+        assert!(
+            docker.can_parse(r#"{"log":"hi\n","stream":"stdout","time":"2024-01-15T10:30:00Z"}"#)
+        );
+        assert!(!docker.can_parse(r#"{"level":"info","msg":"hi"}"#));
+        assert!(cri.can_parse("2024-01-15T10:30:00.123456789Z stderr F boom"));
+        assert!(!cri.can_parse("yesterday stdout F boom"));
+        assert!(!cri.can_parse("[2024-01-15 10:30:00] local.ERROR: Test"));
+    }
+}
@@ -0,0 +1,44 @@
+//! Per-source settings consulted while parsing.
+
+use crate::domain::log_watching::{LevelAliases, LogLevel, LogSource, SourceTimezone};
+
+/// Settings of the source a line was read from.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ParseContext {
+    /// Timezone used for timestamps written without an offset.
+    pub timezone: SourceTimezone,
+    /// Whether ANSI escapes are stripped from lines before parsing.
+    pub strip_ansi: bool,
+    /// The source's own level names, consulted before the built-in ones.
+    pub level_aliases: LevelAliases,
+}
+
+impl ParseContext {
+    /// Creates a context that reads timestamps without an offset as UTC.
+    pub fn utc() -> Self {
+        Self {
+            timezone: SourceTimezone::UTC,
+            strip_ansi: false,
+            level_aliases: LevelAliases::default(),
+        }
+    }
+
+    /// Creates the context for a watched source.
+    pub fn for_source(source: &LogSource) -> Self {
+        Self {
+            timezone: source.timezone,
+            strip_ansi: source.strip_ansi,
+            level_aliases: source.level_aliases.clone(),
+        }
+    }
+
+    /// Resolves a level token through the source's aliases, then the built-in names.
+    pub fn level(&self, token: &str) -> LogLevel {
+        self.level_aliases.resolve(token)
+    }
+
+    /// Looks up a level token in the source's aliases only.
+    pub fn level_alias(&self, token: &str) -> Option<LogLevel> {
+        self.level_aliases.get(token)
+    }
+}
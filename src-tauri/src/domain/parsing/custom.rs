@@ -0,0 +1,386 @@
+//! User-defined regex parsers.
+//!
+//! Lets users describe in-house formats with a regex using named capture
+//! groups. The `timestamp`, `level`, `message` and `channel` groups map onto
+//! the entry; any other named group is stored in the context.
+//!
+//! Patterns can also be written as grok expressions, which are expanded
+//! against a [`GrokLibrary`] before compiling. Grok fields declared as
+//! `:int` or `:float` are stored in the context as numbers.
+
+use chrono::{DateTime, NaiveDate, NaiveDateTime, Utc};
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+
+use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::SourceTimezone;
+
+use super::grok::{FieldType, GrokLibrary};
+use super::{timestamp, LogParser, ParseContext};
+
+/// Named groups that map onto entry fields instead of the context.
+const RESERVED_GROUPS: &[&str] = &["timestamp", "level", "message", "channel"];
+
+/// Syntax of a custom parser pattern.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PatternSyntax {
+    /// Plain regex with named capture groups.
+    #[default]
+    Regex,
+    /// Grok expression such as `%{IPORHOST:client} %{GREEDYDATA:message}`.
+    Grok,
+}
+
+/// A user-defined parser definition.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomParserDefinition {
+    /// Unique parser name.
+    pub name: String,
+    /// Regex or grok expression matching the first line of an entry.
+    pub pattern: String,
+    /// Syntax of the pattern.
+    #[serde(default)]
+    pub syntax: PatternSyntax,
+    /// chrono format string for the `timestamp` group (e.g. "%d.%m.%Y %H:%M:%S").
+    #[serde(default)]
+    pub timestamp_format: Option<String>,
+    /// Regex matching continuation lines that belong to the previous entry.
+    #[serde(default)]
+    pub continuation_pattern: Option<String>,
+}
+
+/// Errors that can occur when building a custom parser.
+#[derive(Debug, thiserror::Error)]
+pub enum CustomParserError {
+    #[error("Parser name cannot be empty")]
+    EmptyName,
+    #[error("Invalid pattern: {0}")]
+    InvalidPattern(String),
+    #[error("Invalid grok pattern: {0}")]
+    InvalidGrok(String),
+    #[error("Invalid continuation pattern: {0}")]
+    InvalidContinuationPattern(String),
+}
+
+/// Parser built from a user-defined regex.
+#[derive(Debug, Clone)]
+pub struct CustomRegexParser {
+    definition: CustomParserDefinition,
+    regex: Regex,
+    continuation: Option<Regex>,
+    conversions: HashMap<String, FieldType>,
+}
+
+impl CustomRegexParser {
+    /// Build a parser from a definition, compiling its patterns.
+    ///
+    /// Grok patterns are expanded against the standard library.
+    pub fn new(definition: CustomParserDefinition) -> Result<Self, CustomParserError> {
+        Self::with_library(definition, GrokLibrary::standard_ref())
+    }
+
+    /// Build a parser from a definition, expanding grok patterns against the given library.
+    pub fn with_library(
+        definition: CustomParserDefinition,
+        library: &GrokLibrary,
+    ) -> Result<Self, CustomParserError> {
+        if definition.name.trim().is_empty() {
+            return Err(CustomParserError::EmptyName);
+        }
+
+        let (pattern, conversions) = match definition.syntax {
+            PatternSyntax::Regex => (definition.pattern.clone(), HashMap::new()),
+            PatternSyntax::Grok => {
+                let grok = library
+                    .compile(&definition.pattern)
+                    .map_err(|e| CustomParserError::InvalidGrok(e.to_string()))?;
+                (grok.regex, grok.conversions)
+            }
+        };
+        let regex =
+            Regex::new(&pattern).map_err(|e| CustomParserError::InvalidPattern(e.to_string()))?;
+        let continuation = definition
+            .continuation_pattern
+            .as_deref()
+            .filter(|p| !p.is_empty())
+            .map(Regex::new)
+            .transpose()
+            .map_err(|e| CustomParserError::InvalidContinuationPattern(e.to_string()))?;
+
+        Ok(Self {
+            definition,
+            regex,
+            continuation,
+            conversions,
+        })
+    }
+
+    /// Returns the definition this parser was built from.
+    pub fn definition(&self) -> &CustomParserDefinition {
+        &self.definition
+    }
+
+    /// Parse the timestamp using the configured format, or ISO-8601 if none is set.
+    fn parse_timestamp(
+        &self,
+        timestamp_str: &str,
+        timezone: &SourceTimezone,
+    ) -> Option<DateTime<Utc>> {
+        let Some(format) = self.definition.timestamp_format.as_deref() else {
+            return timestamp::parse_iso8601_or_epoch(timestamp_str, timezone);
+        };
+
+        DateTime::parse_from_str(timestamp_str, format)
+            .map(|dt| dt.with_timezone(&Utc))
+            .ok()
+            .or_else(|| {
+                NaiveDateTime::parse_from_str(timestamp_str, format)
+                    .ok()
+                    .map(|dt| timezone.to_utc(dt))
+            })
+            .or_else(|| {
+                NaiveDate::parse_from_str(timestamp_str, format)
+                    .ok()
+                    .and_then(|d| d.and_hms_opt(0, 0, 0))
+                    .map(|dt| timezone.to_utc(dt))
+            })
+    }
+
+    /// Convert a captured context value according to its grok type, if any.
+    fn context_value(&self, name: &str, value: &str) -> Value {
+        match self.conversions.get(name) {
+            Some(FieldType::Int) => value.parse::<i64>().map(Value::from).ok(),
+            Some(FieldType::Float) => value.parse::<f64>().ok().map(Value::from),
+            None => None,
+        }
+        .unwrap_or_else(|| json!(value))
+    }
+
+    /// Check if a line continues the previous entry.
+    fn is_continuation(&self, line: &str) -> bool {
+        self.continuation
+            .as_ref()
+            .is_some_and(|re| re.is_match(line))
+    }
+}
+
+impl LogParser for CustomRegexParser {
+    fn name(&self) -> &str {
+        &self.definition.name
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let captures = self.regex.captures(line)?;
+
+        let timestamp = captures
+            .name("timestamp")
+            .and_then(|m| self.parse_timestamp(m.as_str(), &parse_context.timezone));
+        let level = captures
+            .name("level")
+            .map(|m| parse_context.level(m.as_str()))
+            .unwrap_or_default();
+        let message = captures.name("message").map(|m| m.as_str()).unwrap_or(line);
+        let channel = captures
+            .name("channel")
+            .map(|m| m.as_str().to_string())
+            .filter(|c| !c.is_empty());
+
+        let context: Map<String, Value> = self
+            .regex
+            .capture_names()
+            .flatten()
+            .filter(|name| !RESERVED_GROUPS.contains(name))
+            .filter_map(|name| {
+                let value = captures.name(name)?.as_str();
+                Some((name.to_string(), self.context_value(name, value)))
+            })
+            .collect();
+        let context = if context.is_empty() {
+            None
+        } else {
+            Some(Value::Object(context))
+        };
+
+        Some(LogEntry::new(
+            format!("custom-{}", line_number),
+            timestamp,
+            level,
+            message.to_string(),
+            line.to_string(),
+            line_number,
+            context,
+            None,
+            channel,
+        ))
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        self.regex.is_match(line)
+    }
+
+    fn parse_multiline_with(
+        &self,
+        lines: &[&str],
+        start_line: u64,
+        parse_context: &ParseContext,
+    ) -> Option<(LogEntry, usize)> {
+        let first_line = lines.first()?;
+        let entry = self.parse_with(first_line, start_line, parse_context)?;
+
+        let continuation: Vec<String> = lines
+            .iter()
+            .skip(1)
+            .take_while(|line| self.is_continuation(line) && !self.can_parse(line))
+            .map(|line| line.to_string())
+            .collect();
+
+        if continuation.is_empty() {
+            return Some((entry, 1));
+        }
+
+        let consumed = continuation.len() + 1;
+        Some((entry.with_stack_trace(continuation), consumed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::log_watching::log_level::LogLevel;
+
+    fn definition(pattern: &str) -> CustomParserDefinition {
+        CustomParserDefinition {
+            name: "Billing".to_string(),
+            pattern: pattern.to_string(),
+            syntax: PatternSyntax::Regex,
+            timestamp_format: Some("%d.%m.%Y %H:%M:%S".to_string()),
+            continuation_pattern: Some(r"^\s+".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_parse_named_groups() {
+        let parser = CustomRegexParser::new(definition(
+            r"^(?P<timestamp>\S+ \S+) <(?P<level>\w+)> \[(?P<channel>\w+)\] req=(?P<request_id>\w+) (?P<message>.*)$",
+        ))
+        .unwrap();
+        let line = "15.01.2024 10:30:00 <WARN> [invoices] req=ab12 Payment retried";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(parser.name(), "Billing");
+        assert_eq!(entry.level, LogLevel::Warning);
+        assert_eq!(entry.channel.as_deref(), Some("invoices"));
+        assert_eq!(entry.message, "Payment retried");
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T10:30:00+00:00"
+        );
+        assert_eq!(
+            entry.context.expect("Should have context")["request_id"],
+            "ab12"
+        );
+    }
+
+    #[test]
+    fn test_parse_without_message_group_uses_line() {
+        let parser = CustomRegexParser::new(definition(r"^(?P<level>[A-Z]+) ")).unwrap();
+
+        let entry = parser
+            .parse("ERROR disk full", 1)
+            .expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "ERROR disk full");
+        assert!(entry.context.is_none());
+    }
+
+    #[test]
+    fn test_parse_multiline_with_continuation() {
+        let parser =
+            CustomRegexParser::new(definition(r"^(?P<level>[A-Z]+) (?P<message>.*)$")).unwrap();
+        let lines = vec!["ERROR failed", "  detail one", "  detail two", "INFO next"];
+
+        let (entry, consumed) = parser.parse_multiline(&lines, 1).expect("Should parse");
+
+        assert_eq!(consumed, 3);
+        assert_eq!(
+            entry.stack_trace.unwrap(),
+            vec!["  detail one", "  detail two"]
+        );
+    }
+
+    #[test]
+    fn test_parse_grok_pattern() {
+        let mut grok = definition(
+            "%{TIMESTAMP_ISO8601:timestamp} %{LOGLEVEL:level} %{IPORHOST:client} took=%{NUMBER:duration:float}ms status=%{INT:status:int} %{GREEDYDATA:message}",
+        );
+        grok.syntax = PatternSyntax::Grok;
+        grok.timestamp_format = None;
+        let parser = CustomRegexParser::new(grok).unwrap();
+        let line = "2024-01-15T10:30:00Z ERROR 10.0.0.1 took=12.5ms status=502 Upstream failed";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "Upstream failed");
+        assert!(entry.timestamp.is_some());
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["client"], "10.0.0.1");
+        assert_eq!(context["duration"], 12.5);
+        assert_eq!(context["status"], 502);
+    }
+
+    #[test]
+    fn test_grok_with_custom_library() {
+        let mut library = GrokLibrary::standard();
+        library.add_pattern("TICKET", r"[A-Z]+-\d+").unwrap();
+        let mut grok = definition("%{TICKET:ticket} %{GREEDYDATA:message}");
+        grok.syntax = PatternSyntax::Grok;
+
+        assert!(matches!(
+            CustomRegexParser::new(grok.clone()),
+            Err(CustomParserError::InvalidGrok(_))
+        ));
+
+        let parser = CustomRegexParser::with_library(grok, &library).unwrap();
+        let entry = parser
+            .parse("OPS-42 Deploy started", 1)
+            .expect("Should parse log line");
+        assert_eq!(
+            entry.context.expect("Should have context")["ticket"],
+            "OPS-42"
+        );
+    }
+
+    #[test]
+    fn test_invalid_definitions() {
+        assert!(matches!(
+            CustomRegexParser::new(definition(r"^(?P<level>")),
+            Err(CustomParserError::InvalidPattern(_))
+        ));
+
+        let mut invalid_continuation = definition(r"^(?P<message>.*)$");
+        invalid_continuation.continuation_pattern = Some("[".to_string());
+        assert!(matches!(
+            CustomRegexParser::new(invalid_continuation),
+            Err(CustomParserError::InvalidContinuationPattern(_))
+        ));
+
+        let mut unnamed = definition(r"^(?P<message>.*)$");
+        unnamed.name = " ".to_string();
+        assert!(matches!(
+            CustomRegexParser::new(unnamed),
+            Err(CustomParserError::EmptyName)
+        ));
+    }
+}
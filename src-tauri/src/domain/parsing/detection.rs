@@ -0,0 +1,155 @@
+//! Log format detection.
+//!
+//! Scores parsers against a sample of lines from a source, so the source
+//! can be pinned to the parser that fits it best instead of trying every
+//! parser on every line.
+
+use super::LogParser;
+
+/// Detects the log format of a sample of lines.
+pub struct FormatDetector;
+
+impl FormatDetector {
+    /// Number of lines to sample from the start and from the end of a file.
+    pub const SAMPLE_LINES: usize = 50;
+
+    /// Minimum share of non-blank sample lines a parser must cover to be picked.
+    pub const MIN_COVERAGE: f64 = 0.5;
+
+    /// Score how well a parser fits a sample.
+    ///
+    /// Returns the share of non-blank lines covered by the parser, counting
+    /// continuation lines (stack traces etc.) consumed by multiline parsing.
+    pub fn score(parser: &dyn LogParser, lines: &[&str]) -> f64 {
+        let total = lines.iter().filter(|l| !l.trim().is_empty()).count();
+        if total == 0 {
+            return 0.0;
+        }
+
+        let mut covered = 0;
+        let mut i = 0;
+        while i < lines.len() {
+            let remaining = &lines[i..];
+            if !remaining[0].trim().is_empty() && parser.can_parse(remaining[0]) {
+                if let Some((_, consumed)) = parser.parse_multiline(remaining, i as u64 + 1) {
+                    let consumed = consumed.max(1);
+                    covered += remaining[..consumed]
+                        .iter()
+                        .filter(|l| !l.trim().is_empty())
+                        .count();
+                    i += consumed;
+                    continue;
+                }
+            }
+            i += 1;
+        }
+
+        covered as f64 / total as f64
+    }
+
+    /// Pick the best parser for a sample.
+    ///
+    /// Returns None if no parser reaches the minimum coverage. Ties go to
+    /// the parser listed first.
+    pub fn detect<'a>(parsers: &[&'a dyn LogParser], lines: &[&str]) -> Option<&'a dyn LogParser> {
+        let mut best: Option<(&'a dyn LogParser, f64)> = None;
+
+        for parser in parsers {
+            let score = Self::score(*parser, lines);
+            if score >= Self::MIN_COVERAGE && best.map_or(true, |(_, s)| score > s) {
+                best = Some((*parser, score));
+            }
+        }
+
+        best.map(|(parser, _)| parser)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::parsing::{
+        JsonLinesParser, LaravelLogParser, LogfmtParser, PythonLogParser, SymfonyLogParser,
+        SyslogParser,
+    };
+
+    fn parsers() -> Vec<Box<dyn LogParser>> {
+        vec![
+            Box::new(SymfonyLogParser::new()),
+            Box::new(LaravelLogParser::new()),
+            Box::new(JsonLinesParser::new()),
+            Box::new(PythonLogParser::new()),
+            Box::new(SyslogParser::new()),
+            Box::new(LogfmtParser::new()),
+        ]
+    }
+
+    fn detect(lines: &[&str]) -> Option<String> {
+        let parsers = parsers();
+        let refs: Vec<&dyn LogParser> = parsers.iter().map(|p| p.as_ref()).collect();
+        FormatDetector::detect(&refs, lines).map(|p| p.name().to_string())
+    }
+
+    #[test]
+    fn test_detect_laravel_with_stack_trace() {
+        let lines = [
+            "[2024-01-15 10:30:00] local.ERROR: Something failed",
+            "[stacktrace]",
+            "#0 /var/www/app/Http/Controller.php(42): handle()",
+            "#1 {main}",
+            "",
+            "[2024-01-15 10:31:00] local.INFO: Recovered",
+        ];
+
+        assert_eq!(detect(&lines).as_deref(), Some("Laravel"));
+    }
+
+    #[test]
+    fn test_detect_symfony_iso_timestamps() {
+        let lines = [
+            "[2024-01-15T10:30:00.123456+00:00] request.INFO: Matched route \"home\". [] []",
+            "[2024-01-15T10:30:00.234567+00:00] app.ERROR: Something failed [] []",
+        ];
+
+        assert_eq!(detect(&lines).as_deref(), Some("Symfony"));
+    }
+
+    #[test]
+    fn test_detect_json_and_logfmt() {
+        let json = [
+            r#"{"level":"info","msg":"started"}"#,
+            r#"{"level":"error","msg":"failed"}"#,
+        ];
+        let logfmt = ["level=info msg=started", "level=warn msg=slow"];
+
+        assert_eq!(detect(&json).as_deref(), Some("JSON Lines"));
+        assert_eq!(detect(&logfmt).as_deref(), Some("logfmt"));
+    }
+
+    #[test]
+    fn test_majority_wins_over_ambiguous_lines() {
+        let lines = [
+            "Jan 15 10:30:00 web-1 sshd[123]: Accepted publickey",
+            "Jan 15 10:30:01 web-1 sshd[123]: level=info msg=ambiguous",
+            "Jan 15 10:30:02 web-1 cron[456]: Job started",
+        ];
+
+        assert_eq!(detect(&lines).as_deref(), Some("Syslog"));
+    }
+
+    #[test]
+    fn test_no_detection_below_threshold() {
+        let lines = [
+            "Server starting",
+            "Listening on port 8080",
+            "level=info msg=ready",
+        ];
+
+        assert_eq!(detect(&lines), None);
+        assert_eq!(detect(&[]), None);
+        assert_eq!(
+            FormatDetector::score(&LogfmtParser::new(), &lines),
+            1.0 / 3.0
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Fallback parsing for lines no parser recognises.
+//!
+//! Picks up a leading timestamp in any common format and a level keyword
+//! right after it, so time-based features and error notifications still
+//! work on arbitrary logs:
+//! 2024-01-15 10:30:00 ERROR Something failed
+//! Jan 15 10:30:00 [warn] Disk almost full
+//! 1705314600123 E/ActivityManager: Process crashed
+
+use regex::{Captures, Regex};
+use std::sync::LazyLock;
+
+use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::log_level::LogLevel;
+
+use super::{timestamp, ParseContext};
+
+/// Regex for a level token: `[warn]`, `[3]`, `ERROR:`, `E/` or an upper-case `ERROR`.
+static LEADING_LEVEL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:\[(?P<bracketed>[A-Za-z0-9]+)\]|(?P<colon>[A-Za-z]+):|(?P<letter>[VDIWEFA])/|(?P<word>[A-Z]+)\b)",
+    )
+    .unwrap()
+});
+
+/// Characters separating the timestamp and level from the message.
+const SEPARATORS: &[char] = &[' ', '\t', '-', ':', '|'];
+
+/// Builds entries for lines that no parser recognised.
+#[derive(Debug, Default, Clone)]
+pub struct FallbackParser;
+
+impl FallbackParser {
+    /// Create a new fallback parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Map a level keyword to a level, or None if the word is not one.
+    pub(crate) fn keyword_level(word: &str) -> Option<LogLevel> {
+        match word.to_lowercase().as_str() {
+            "trace" => Some(LogLevel::Trace),
+            "debug" | "dbg" | "verbose" => Some(LogLevel::Debug),
+            "info" | "information" | "inf" => Some(LogLevel::Info),
+            "notice" => Some(LogLevel::Notice),
+            "warn" | "warning" | "wrn" => Some(LogLevel::Warning),
+            "error" | "err" => Some(LogLevel::Error),
+            "critical" | "crit" | "fatal" | "severe" => Some(LogLevel::Critical),
+            "alert" => Some(LogLevel::Alert),
+            "emergency" | "emerg" | "panic" => Some(LogLevel::Emergency),
+            _ => None,
+        }
+    }
+
+    /// Map an Android logcat style single-letter level.
+    pub(crate) fn letter_level(letter: &str) -> Option<LogLevel> {
+        match letter {
+            "V" | "D" => Some(LogLevel::Debug),
+            "I" => Some(LogLevel::Info),
+            "W" => Some(LogLevel::Warning),
+            "E" => Some(LogLevel::Error),
+            "F" | "A" => Some(LogLevel::Critical),
+            _ => None,
+        }
+    }
+
+    /// Map a captured level token to a level, consulting the source's
+    /// aliases before the built-in names.
+    pub(crate) fn captured_level(
+        captures: &Captures,
+        parse_context: &ParseContext,
+    ) -> Option<LogLevel> {
+        let (token, builtin): (_, fn(&str) -> Option<LogLevel>) = match captures.name("letter") {
+            Some(letter) => (letter, Self::letter_level),
+            None => (
+                captures
+                    .name("bracketed")
+                    .or_else(|| captures.name("colon"))
+                    .or_else(|| captures.name("word"))?,
+                Self::keyword_level,
+            ),
+        };
+        parse_context
+            .level_alias(token.as_str())
+            .or_else(|| builtin(token.as_str()))
+    }
+
+    /// Detect a level token at the start of the text.
+    ///
+    /// Returns the level and the length of the token.
+    fn leading_level(text: &str, parse_context: &ParseContext) -> Option<(LogLevel, usize)> {
+        let captures = LEADING_LEVEL_REGEX.captures(text)?;
+        let level = Self::captured_level(&captures, parse_context)?;
+        Some((level, captures.get(0)?.end()))
+    }
+
+    /// Build an entry for the line, picking up what can be detected.
+    pub fn parse(&self, line: &str, line_number: u64, parse_context: &ParseContext) -> LogEntry {
+        let mut entry = LogEntry::from_raw(line.to_string(), line_number);
+        let mut rest = line;
+
+        if let Some((timestamp, end)) = timestamp::detect_leading(line, &parse_context.timezone) {
+            entry.timestamp = Some(timestamp);
+            rest = line[end..].trim_start_matches(SEPARATORS);
+        }
+        if let Some((level, end)) = Self::leading_level(rest, parse_context) {
+            entry.level = level;
+            rest = rest[end..].trim_start_matches(SEPARATORS);
+        }
+
+        if rest.len() < line.len() && !rest.trim().is_empty() {
+            entry.message = rest.trim_end().to_string();
+        }
+        entry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_timestamp_and_level() {
+        let parser = FallbackParser::new();
+        let test_cases = [
+            (
+                "2024-01-15 10:30:00 ERROR Something failed",
+                LogLevel::Error,
+                "Something failed",
+            ),
+            (
+                "Jan 15 10:30:00 [warn] Disk almost full",
+                LogLevel::Warning,
+                "Disk almost full",
+            ),
+            (
+                "1705314600123 E/ActivityManager: Process crashed",
+                LogLevel::Error,
+                "ActivityManager: Process crashed",
+            ),
+            (
+                "15/Jan/2024:10:30:00 +0000 - fatal: out of memory",
+                LogLevel::Critical,
+                "out of memory",
+            ),
+        ];
+
+        for (line, level, message) in test_cases {
+            let entry = parser.parse(line, 1, &ParseContext::utc());
+            assert!(entry.timestamp.is_some(), "Failed for: {}", line);
+            assert_eq!(entry.level, level, "Failed for: {}", line);
+            assert_eq!(entry.message, message, "Failed for: {}", line);
+            assert_eq!(entry.raw, line);
+        }
+    }
+
+    #[test]
+    fn test_level_without_timestamp() {
+        let parser = FallbackParser::new();
+
+        let entry = parser.parse("WARNING: low disk space", 3, &ParseContext::utc());
+
+        assert!(entry.timestamp.is_none());
+        assert_eq!(entry.level, LogLevel::Warning);
+        assert_eq!(entry.message, "low disk space");
+        assert_eq!(entry.line_number, 3);
+    }
+
+    #[test]
+    fn test_plain_text_is_left_alone() {
+        let parser = FallbackParser::new();
+        let lines = [
+            "Server started on port 8080",
+            "Error handling is configured",
+            "INFORMATIONAL notice",
+        ];
+
+        for line in lines {
+            let entry = parser.parse(line, 1, &ParseContext::utc());
+            assert!(entry.timestamp.is_none(), "Failed for: {}", line);
+            assert_eq!(entry.level, LogLevel::Info, "Failed for: {}", line);
+            assert_eq!(entry.message, line, "Failed for: {}", line);
+        }
+    }
+
+    #[test]
+    fn test_level_aliases() {
+        let parser = FallbackParser::new();
+        let parse_context = ParseContext {
+            level_aliases: [("FINE", LogLevel::Debug), ("3", LogLevel::Error)]
+                .into_iter()
+                .collect(),
+            ..ParseContext::default()
+        };
+
+        let entry = parser.parse("2024-01-15 10:30:00 FINE cache warmed", 1, &parse_context);
+        assert_eq!(entry.level, LogLevel::Debug);
+        assert_eq!(entry.message, "cache warmed");
+
+        let entry = parser.parse("[3] disk failure", 1, &parse_context);
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "disk failure");
+    }
+}
@@ -0,0 +1,194 @@
+//! Generic fallback parser.
+//!
+//! Accepts any line no other parser recognised. The timestamp is detected
+//! as in [`FallbackParser`], and the level is taken from the first level
+//! token found anywhere in the line:
+//! - bracketed: `[error]`, `[WARN]`
+//! - colon-suffixed: `error:`, `WARNING:`
+//! - single letter: `E/Tag` (logcat), `E0115 10:30:00.123456` (glog)
+//! - upper-case word: `ERROR`, `FATAL`
+//!
+//! Indented and `at `-prefixed lines following an entry are attached as its
+//! stack trace.
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::log_level::LogLevel;
+
+use super::{FallbackParser, LogParser, ParseContext};
+
+/// Regex for level tokens anywhere in a line.
+static LEVEL_TOKEN_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"\[(?P<bracketed>[A-Za-z]+)\]|\b(?P<colon>[A-Za-z]+):|(?:^|\s)(?P<letter>[VDIWEF])(?:/|\d{4}\s)|\b(?P<word>[A-Z]+)\b",
+    )
+    .unwrap()
+});
+
+/// Generic fallback parser, tried after all other parsers.
+#[derive(Debug, Default, Clone)]
+pub struct GenericParser;
+
+impl GenericParser {
+    /// Create a new generic parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Find the first level token in the line.
+    fn find_level(&self, line: &str, parse_context: &ParseContext) -> Option<LogLevel> {
+        LEVEL_TOKEN_REGEX
+            .captures_iter(line)
+            .find_map(|captures| FallbackParser::captured_level(&captures, parse_context))
+    }
+
+    /// Check if a line continues the previous entry.
+    fn is_continuation(&self, line: &str) -> bool {
+        (line.starts_with([' ', '\t']) && !line.trim().is_empty()) || line.starts_with("at ")
+    }
+}
+
+impl LogParser for GenericParser {
+    fn name(&self) -> &'static str {
+        "Generic"
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        if line.trim().is_empty() {
+            return None;
+        }
+
+        let mut entry = FallbackParser::new().parse(line, line_number, parse_context);
+        entry.id = format!("generic-{}", line_number);
+        if let Some(level) = self.find_level(line, parse_context) {
+            entry.level = level;
+        }
+        Some(entry)
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        !line.trim().is_empty() && !self.is_continuation(line)
+    }
+
+    fn parse_multiline_with(
+        &self,
+        lines: &[&str],
+        start_line: u64,
+        parse_context: &ParseContext,
+    ) -> Option<(LogEntry, usize)> {
+        let first_line = lines.first()?;
+        let entry = self.parse_with(first_line, start_line, parse_context)?;
+
+        let continuation: Vec<String> = lines
+            .iter()
+            .skip(1)
+            .take_while(|line| self.is_continuation(line))
+            .map(|line| line.to_string())
+            .collect();
+        let consumed = 1 + continuation.len();
+
+        if continuation.is_empty() {
+            Some((entry, consumed))
+        } else {
+            Some((entry.with_stack_trace(continuation), consumed))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_level_tokens_anywhere() {
+        let parser = GenericParser::new();
+        let test_cases = [
+            ("worker-3 [error] job 42 failed", LogLevel::Error),
+            ("pid=12 WARNING: cache is cold", LogLevel::Warning),
+            ("db: connection error: timeout", LogLevel::Error),
+            (
+                "03-15 10:30:00.123 E/ActivityManager: crash",
+                LogLevel::Error,
+            ),
+            (
+                "W0115 10:30:00.123456 1 main.go:42] slow",
+                LogLevel::Warning,
+            ),
+            ("node-1 | FATAL out of memory", LogLevel::Critical),
+            ("[main] [debug] loading config", LogLevel::Debug),
+        ];
+
+        for (line, expected) in test_cases {
+            let entry = parser.parse(line, 1).expect("Should parse log line");
+            assert_eq!(entry.level, expected, "Failed for: {}", line);
+        }
+    }
+
+    #[test]
+    fn test_respects_word_boundaries() {
+        let parser = GenericParser::new();
+        let lines = [
+            "TERRORS of the deep: chapter 1",
+            "ERRORS=0 WARNINGS=0",
+            "error_count: 3",
+            "no error found",
+            "Visit https://example.com",
+        ];
+
+        for line in lines {
+            let entry = parser.parse(line, 1).expect("Should parse log line");
+            assert_eq!(entry.level, LogLevel::Info, "Failed for: {}", line);
+        }
+    }
+
+    #[test]
+    fn test_detects_timestamp() {
+        let parser = GenericParser::new();
+
+        let entry = parser
+            .parse("2024-01-15T10:30:00Z svc ERROR boom", 1)
+            .expect("Should parse log line");
+
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T10:30:00+00:00"
+        );
+        assert_eq!(entry.level, LogLevel::Error);
+    }
+
+    #[test]
+    fn test_parse_multiline_continuation() {
+        let parser = GenericParser::new();
+        let lines = [
+            "Exception in worker: ERROR something broke",
+            "    at com.example.Worker.run(Worker.java:42)",
+            "at com.example.Main.main(Main.java:10)",
+            "\tmore detail",
+            "next entry",
+        ];
+
+        let (entry, consumed) = parser.parse_multiline(&lines, 1).expect("Should parse");
+
+        assert_eq!(consumed, 4);
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.stack_trace.as_ref().map(Vec::len), Some(3));
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let parser = GenericParser::new();
+
+        assert!(parser.can_parse("anything at all"));
+        assert!(!parser.can_parse("    at com.example.Worker.run(Worker.java:42)"));
+        assert!(!parser.can_parse("at com.example.Main.main(Main.java:10)"));
+        assert!(!parser.can_parse("   "));
+        assert!(parser.parse("   ", 1).is_none());
+    }
+}
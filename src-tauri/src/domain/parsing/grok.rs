@@ -0,0 +1,390 @@
+//! Grok pattern support.
+//!
+//! Expands Logstash-style grok expressions (`%{IPORHOST:client} %{HTTPDATE:timestamp}`)
+//! into regexes with named capture groups, so they can back a custom parser.
+//! The standard pattern library is bundled, adapted to the `regex` crate
+//! (no lookaround or atomic groups), and users can add their own patterns.
+
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::LazyLock;
+
+/// Regex for grok references: `%{PATTERN}`, `%{PATTERN:field}` or `%{PATTERN:field:type}`.
+static REFERENCE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"%\{(\w+)(?::([\w@.\[\]-]+))?(?::(int|integer|long|float|double))?\}").unwrap()
+});
+
+/// Regex for valid pattern names.
+static PATTERN_NAME_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^\w+$").unwrap());
+
+/// Maximum nesting depth when expanding pattern references.
+const MAX_DEPTH: usize = 32;
+
+/// The bundled standard pattern library.
+static STANDARD_LIBRARY: LazyLock<GrokLibrary> = LazyLock::new(|| GrokLibrary {
+    patterns: STANDARD_PATTERNS
+        .iter()
+        .map(|(name, definition)| (name.to_string(), definition.to_string()))
+        .collect(),
+});
+
+/// Standard grok patterns, following the Logstash `grok-patterns` file.
+const STANDARD_PATTERNS: &[(&str, &str)] = &[
+    ("USERNAME", r"[a-zA-Z0-9._-]+"),
+    ("USER", r"%{USERNAME}"),
+    (
+        "EMAILLOCALPART",
+        r"[a-zA-Z0-9!#$%&'*+/=?^_`{|}~-]+(?:\.[a-zA-Z0-9!#$%&'*+/=?^_`{|}~-]+)*",
+    ),
+    ("EMAILADDRESS", r"%{EMAILLOCALPART}@%{HOSTNAME}"),
+    ("INT", r"[+-]?[0-9]+"),
+    ("BASE10NUM", r"[+-]?(?:[0-9]+(?:\.[0-9]+)?|\.[0-9]+)"),
+    ("NUMBER", r"%{BASE10NUM}"),
+    ("BASE16NUM", r"[+-]?(?:0x)?[0-9A-Fa-f]+"),
+    ("POSINT", r"\b[1-9][0-9]*\b"),
+    ("NONNEGINT", r"\b[0-9]+\b"),
+    ("WORD", r"\b\w+\b"),
+    ("NOTSPACE", r"\S+"),
+    ("SPACE", r"\s*"),
+    ("DATA", r".*?"),
+    ("GREEDYDATA", r".*"),
+    (
+        "QUOTEDSTRING",
+        r#""(?:\\.|[^\\"])*"|'(?:\\.|[^\\'])*'|`(?:\\.|[^\\`])*`"#,
+    ),
+    ("QS", r"%{QUOTEDSTRING}"),
+    (
+        "UUID",
+        r"[A-Fa-f0-9]{8}-(?:[A-Fa-f0-9]{4}-){3}[A-Fa-f0-9]{12}",
+    ),
+    (
+        "MAC",
+        r"(?:[A-Fa-f0-9]{2}[:-]){5}[A-Fa-f0-9]{2}|(?:[A-Fa-f0-9]{4}\.){2}[A-Fa-f0-9]{4}",
+    ),
+    (
+        "IPV6",
+        r"(?:[0-9A-Fa-f]{0,4}:){2,7}[0-9A-Fa-f]{0,4}(?:%\w+)?",
+    ),
+    (
+        "IPV4",
+        r"(?:(?:25[0-5]|2[0-4][0-9]|[01]?[0-9][0-9]?)\.){3}(?:25[0-5]|2[0-4][0-9]|[01]?[0-9][0-9]?)",
+    ),
+    ("IP", r"%{IPV6}|%{IPV4}"),
+    (
+        "HOSTNAME",
+        r"\b[0-9A-Za-z][0-9A-Za-z-]{0,62}(?:\.[0-9A-Za-z][0-9A-Za-z-]{0,62})*\.?",
+    ),
+    ("IPORHOST", r"%{IP}|%{HOSTNAME}"),
+    ("HOSTPORT", r"%{IPORHOST}:%{POSINT}"),
+    ("UNIXPATH", r"(?:/[\w%!$@:.,+~-]*)+"),
+    ("WINPATH", r"(?:[A-Za-z]+:|\\)(?:\\[^\\?*]*)+"),
+    ("PATH", r"%{UNIXPATH}|%{WINPATH}"),
+    ("URIPROTO", r"[A-Za-z][A-Za-z0-9+.-]+"),
+    ("URIHOST", r"%{IPORHOST}(?::%{POSINT})?"),
+    ("URIPATH", r"(?:/[A-Za-z0-9$.+!*'(){},~:;=@#%&_-]*)+"),
+    ("URIPARAM", r"\?[A-Za-z0-9$.+!*'|(){},~@#%&/=:;_?\[\]<>-]*"),
+    ("URIPATHPARAM", r"%{URIPATH}(?:%{URIPARAM})?"),
+    (
+        "URI",
+        r"%{URIPROTO}://(?:%{USER}(?::[^@]*)?@)?(?:%{URIHOST})?(?:%{URIPATHPARAM})?",
+    ),
+    (
+        "MONTH",
+        r"\b(?:[Jj]an(?:uary)?|[Ff]eb(?:ruary)?|[Mm]ar(?:ch)?|[Aa]pr(?:il)?|[Mm]ay|[Jj]un(?:e)?|[Jj]ul(?:y)?|[Aa]ug(?:ust)?|[Ss]ep(?:tember)?|[Oo]ct(?:ober)?|[Nn]ov(?:ember)?|[Dd]ec(?:ember)?)\b",
+    ),
+    ("MONTHNUM", r"0?[1-9]|1[0-2]"),
+    ("MONTHNUM2", r"0[1-9]|1[0-2]"),
+    ("MONTHDAY", r"0[1-9]|[12][0-9]|3[01]|[1-9]"),
+    (
+        "DAY",
+        r"Mon(?:day)?|Tue(?:sday)?|Wed(?:nesday)?|Thu(?:rsday)?|Fri(?:day)?|Sat(?:urday)?|Sun(?:day)?",
+    ),
+    ("YEAR", r"(?:\d\d){1,2}"),
+    ("HOUR", r"2[0123]|[01]?[0-9]"),
+    ("MINUTE", r"[0-5][0-9]"),
+    ("SECOND", r"(?:[0-5]?[0-9]|60)(?:[:.,][0-9]+)?"),
+    ("TIME", r"%{HOUR}:%{MINUTE}(?::%{SECOND})"),
+    ("DATE_US", r"%{MONTHNUM}[/-]%{MONTHDAY}[/-]%{YEAR}"),
+    ("DATE_EU", r"%{MONTHDAY}[./-]%{MONTHNUM}[./-]%{YEAR}"),
+    ("ISO8601_TIMEZONE", r"Z|[+-]%{HOUR}(?::?%{MINUTE})"),
+    ("ISO8601_SECOND", r"%{SECOND}"),
+    (
+        "TIMESTAMP_ISO8601",
+        r"%{YEAR}-%{MONTHNUM}-%{MONTHDAY}[T ]%{HOUR}:?%{MINUTE}(?::?%{SECOND})?%{ISO8601_TIMEZONE}?",
+    ),
+    ("DATE", r"%{DATE_US}|%{DATE_EU}"),
+    ("DATESTAMP", r"%{DATE}[- ]%{TIME}"),
+    ("TZ", r"[APMCE][SD]T|UTC"),
+    (
+        "DATESTAMP_RFC822",
+        r"%{DAY} %{MONTH} %{MONTHDAY} %{YEAR} %{TIME} %{TZ}",
+    ),
+    (
+        "DATESTAMP_OTHER",
+        r"%{DAY} %{MONTH} %{MONTHDAY} %{TIME} %{TZ} %{YEAR}",
+    ),
+    ("HTTPDATE", r"%{MONTHDAY}/%{MONTH}/%{YEAR}:%{TIME} %{INT}"),
+    ("SYSLOGTIMESTAMP", r"%{MONTH} +%{MONTHDAY} %{TIME}"),
+    ("PROG", r"[\x21-\x5a\x5c\x5e-\x7e]+"),
+    ("SYSLOGPROG", r"%{PROG:program}(?:\[%{POSINT:pid}\])?"),
+    ("SYSLOGHOST", r"%{IPORHOST}"),
+    (
+        "SYSLOGBASE",
+        r"%{SYSLOGTIMESTAMP:timestamp} %{SYSLOGHOST:logsource} %{SYSLOGPROG}:",
+    ),
+    (
+        "LOGLEVEL",
+        r"[Aa]lert|ALERT|[Tt]race|TRACE|[Dd]ebug|DEBUG|[Nn]otice|NOTICE|[Ii]nfo?(?:rmation)?|INFO?(?:RMATION)?|[Ww]arn?(?:ing)?|WARN?(?:ING)?|[Ee]rr?(?:or)?|ERR?(?:OR)?|[Cc]rit?(?:ical)?|CRIT?(?:ICAL)?|[Ff]atal|FATAL|[Ss]evere|SEVERE|EMERG(?:ENCY)?|[Ee]merg(?:ency)?",
+    ),
+    ("HTTPDUSER", r"%{EMAILADDRESS}|%{USER}"),
+    (
+        "COMMONAPACHELOG",
+        r#"%{IPORHOST:clientip} %{HTTPDUSER:ident} %{USER:auth} \[%{HTTPDATE:timestamp}\] "(?:%{WORD:verb} %{NOTSPACE:request}(?: HTTP/%{NUMBER:httpversion})?|%{DATA:rawrequest})" %{NUMBER:response:int} (?:%{NUMBER:bytes:int}|-)"#,
+    ),
+    (
+        "COMBINEDAPACHELOG",
+        r"%{COMMONAPACHELOG} %{QS:referrer} %{QS:agent}",
+    ),
+];
+
+/// Type conversion applied to a captured field.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FieldType {
+    Int,
+    Float,
+}
+
+/// Errors that can occur when expanding grok expressions.
+#[derive(Debug, thiserror::Error)]
+pub enum GrokError {
+    #[error("Unknown grok pattern: {0}")]
+    UnknownPattern(String),
+    #[error("Invalid grok pattern name: {0}")]
+    InvalidPatternName(String),
+    #[error("Grok pattern nesting too deep: {0}")]
+    RecursionLimit(String),
+}
+
+/// A grok expression expanded to a regex.
+#[derive(Debug, Clone)]
+pub struct GrokPattern {
+    /// The expanded regex, with a named group per grok field.
+    pub regex: String,
+    /// Type conversions per field name.
+    pub conversions: HashMap<String, FieldType>,
+}
+
+/// A library of named grok patterns.
+#[derive(Debug, Clone)]
+pub struct GrokLibrary {
+    patterns: HashMap<String, String>,
+}
+
+impl GrokLibrary {
+    /// Create a library with the bundled standard patterns.
+    pub fn standard() -> Self {
+        STANDARD_LIBRARY.clone()
+    }
+
+    /// Returns the bundled standard library without cloning it.
+    pub fn standard_ref() -> &'static Self {
+        &STANDARD_LIBRARY
+    }
+
+    /// Add or replace a pattern.
+    pub fn add_pattern(&mut self, name: &str, definition: &str) -> Result<(), GrokError> {
+        if !PATTERN_NAME_REGEX.is_match(name) {
+            return Err(GrokError::InvalidPatternName(name.to_string()));
+        }
+        self.patterns
+            .insert(name.to_string(), definition.to_string());
+        Ok(())
+    }
+
+    /// Check if a pattern with the given name exists.
+    pub fn contains(&self, name: &str) -> bool {
+        self.patterns.contains_key(name)
+    }
+
+    /// Expand a grok expression into a regex.
+    pub fn compile(&self, expression: &str) -> Result<GrokPattern, GrokError> {
+        let mut conversions = HashMap::new();
+        let regex = self.expand(expression, 0, &mut conversions)?;
+        Ok(GrokPattern { regex, conversions })
+    }
+
+    /// Recursively expand pattern references.
+    fn expand(
+        &self,
+        expression: &str,
+        depth: usize,
+        conversions: &mut HashMap<String, FieldType>,
+    ) -> Result<String, GrokError> {
+        if depth > MAX_DEPTH {
+            return Err(GrokError::RecursionLimit(expression.to_string()));
+        }
+
+        let mut result = String::with_capacity(expression.len());
+        let mut last_end = 0;
+
+        for captures in REFERENCE_REGEX.captures_iter(expression) {
+            let reference = captures.get(0).unwrap();
+            let name = &captures[1];
+            let definition = self
+                .patterns
+                .get(name)
+                .ok_or_else(|| GrokError::UnknownPattern(name.to_string()))?;
+            let expanded = self.expand(definition, depth + 1, conversions)?;
+
+            result.push_str(&expression[last_end..reference.start()]);
+            match captures.get(2) {
+                Some(field) => {
+                    let field = Self::field_name(field.as_str());
+                    match captures.get(3).map(|m| m.as_str()) {
+                        Some("int" | "integer" | "long") => {
+                            conversions.insert(field.clone(), FieldType::Int);
+                        }
+                        Some(_) => {
+                            conversions.insert(field.clone(), FieldType::Float);
+                        }
+                        None => {}
+                    }
+                    result.push_str(&format!("(?P<{}>{})", field, expanded));
+                }
+                None => result.push_str(&format!("(?:{})", expanded)),
+            }
+            last_end = reference.end();
+        }
+
+        result.push_str(&expression[last_end..]);
+        Ok(result)
+    }
+
+    /// Normalize a grok field name into a valid capture group name.
+    ///
+    /// `[http][status]` becomes `http.status`, a leading `@` is dropped and
+    /// other unsupported characters are replaced with underscores.
+    fn field_name(field: &str) -> String {
+        let field = field.trim_start_matches('@');
+        let field = if field.starts_with('[') {
+            field
+                .split(['[', ']'])
+                .filter(|part| !part.is_empty())
+                .collect::<Vec<_>>()
+                .join(".")
+        } else {
+            field.to_string()
+        };
+        field
+            .chars()
+            .map(|c| {
+                if c.is_alphanumeric() || matches!(c, '_' | '.' | '[' | ']') {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+}
+
+impl Default for GrokLibrary {
+    fn default() -> Self {
+        Self::standard()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard_patterns_compile() {
+        let library = GrokLibrary::standard();
+        for (name, _) in STANDARD_PATTERNS {
+            let pattern = library
+                .compile(&format!("%{{{}}}", name))
+                .unwrap_or_else(|e| panic!("Failed to expand {}: {}", name, e));
+            assert!(
+                Regex::new(&pattern.regex).is_ok(),
+                "Failed to compile: {}",
+                name
+            );
+        }
+    }
+
+    #[test]
+    fn test_compile_named_fields_and_types() {
+        let library = GrokLibrary::standard();
+        let pattern = library
+            .compile(r"%{IPORHOST:client} \[%{HTTPDATE:@timestamp}\] %{NUMBER:[http][status]:int}")
+            .unwrap();
+        let regex = Regex::new(&format!("^{}$", pattern.regex)).unwrap();
+
+        let captures = regex
+            .captures("10.0.0.1 [15/Jan/2024:10:30:00 +0000] 404")
+            .expect("Should match");
+
+        assert_eq!(&captures["client"], "10.0.0.1");
+        assert_eq!(&captures["timestamp"], "15/Jan/2024:10:30:00 +0000");
+        assert_eq!(&captures["http.status"], "404");
+        assert_eq!(
+            pattern.conversions.get("http.status"),
+            Some(&FieldType::Int)
+        );
+    }
+
+    #[test]
+    fn test_combined_apache_log() {
+        let library = GrokLibrary::standard();
+        let pattern = library.compile("%{COMBINEDAPACHELOG}").unwrap();
+        let regex = Regex::new(&format!("^{}$", pattern.regex)).unwrap();
+        let line = r#"127.0.0.1 - frank [15/Jan/2024:10:30:00 +0000] "GET /index.php HTTP/1.1" 200 2326 "http://example.com/" "Mozilla/5.0""#;
+
+        let captures = regex.captures(line).expect("Should match");
+
+        assert_eq!(&captures["clientip"], "127.0.0.1");
+        assert_eq!(&captures["verb"], "GET");
+        assert_eq!(&captures["request"], "/index.php");
+        assert_eq!(&captures["response"], "200");
+        assert_eq!(&captures["agent"], r#""Mozilla/5.0""#);
+    }
+
+    #[test]
+    fn test_custom_patterns() {
+        let mut library = GrokLibrary::standard();
+        library.add_pattern("ORDER_ID", r"ORD-\d{6}").unwrap();
+        library
+            .add_pattern("ORDER_LINE", r"%{LOGLEVEL:level} order=%{ORDER_ID:order}")
+            .unwrap();
+
+        let pattern = library.compile("%{ORDER_LINE}").unwrap();
+        let regex = Regex::new(&pattern.regex).unwrap();
+        let captures = regex
+            .captures("WARN order=ORD-123456")
+            .expect("Should match");
+
+        assert_eq!(&captures["level"], "WARN");
+        assert_eq!(&captures["order"], "ORD-123456");
+        assert!(matches!(
+            library.add_pattern("BAD NAME", "x"),
+            Err(GrokError::InvalidPatternName(_))
+        ));
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut library = GrokLibrary::standard();
+
+        assert!(matches!(
+            library.compile("%{NOPE:x}"),
+            Err(GrokError::UnknownPattern(name)) if name == "NOPE"
+        ));
+
+        library.add_pattern("LOOP", "%{LOOP}").unwrap();
+        assert!(matches!(
+            library.compile("%{LOOP}"),
+            Err(GrokError::RecursionLimit(_))
+        ));
+    }
+}
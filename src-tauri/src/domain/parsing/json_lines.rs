@@ -0,0 +1,307 @@
+//! JSON Lines log parser.
+//!
+//! Parses logs that emit one JSON object per line, as produced by
+//! Monolog's JsonFormatter, pino, bunyan, zap and similar libraries:
+//! {"time":"2024-01-15T10:30:00Z","level":"error","msg":"Message","user_id":123}
+
+use chrono::{DateTime, Utc};
+use serde_json::{Map, Value};
+
+use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::log_level::LogLevel;
+use crate::domain::log_watching::SourceTimezone;
+
+use super::{timestamp, LogParser, ParseContext};
+
+/// Keys that may hold the log level, in order of preference.
+const LEVEL_KEYS: &[&str] = &["level_name", "level", "severity", "lvl"];
+
+/// Keys that may hold the timestamp, in order of preference.
+const TIMESTAMP_KEYS: &[&str] = &["time", "timestamp", "@timestamp", "ts", "datetime"];
+
+/// Keys that may hold the message, in order of preference.
+const MESSAGE_KEYS: &[&str] = &["msg", "message"];
+
+/// Keys that may hold the channel or logger name, in order of preference.
+const CHANNEL_KEYS: &[&str] = &["channel", "logger"];
+
+/// JSON Lines log parser.
+#[derive(Debug, Default, Clone)]
+pub struct JsonLinesParser;
+
+impl JsonLinesParser {
+    /// Create a new JSON Lines parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse a JSON object from a line, if the line contains one.
+    fn parse_object(&self, line: &str) -> Option<Map<String, Value>> {
+        let trimmed = line.trim();
+        if !trimmed.starts_with('{') || !trimmed.ends_with('}') {
+            return None;
+        }
+        match serde_json::from_str(trimmed) {
+            Ok(Value::Object(map)) => Some(map),
+            _ => None,
+        }
+    }
+
+    /// Remove and return the first key present in the object.
+    fn take_first(&self, object: &mut Map<String, Value>, keys: &[&str]) -> Option<Value> {
+        keys.iter().find_map(|key| object.remove(*key))
+    }
+
+    /// Parse a level value, which may be a name or a number.
+    fn parse_level(&self, value: &Value, parse_context: &ParseContext) -> Option<LogLevel> {
+        match value {
+            Value::String(s) => parse_context.level_alias(s).or_else(|| {
+                Some(match s.trim().parse::<i64>() {
+                    Ok(n) => Self::level_from_number(n),
+                    Err(_) => LogLevel::parse(s),
+                })
+            }),
+            Value::Number(n) => parse_context
+                .level_alias(&n.to_string())
+                .or_else(|| n.as_i64().map(Self::level_from_number)),
+            _ => None,
+        }
+    }
+
+    /// Map a numeric level to a LogLevel.
+    ///
+    /// Values below 100 use the pino/bunyan scale (10 trace .. 60 fatal),
+    /// larger values use the Monolog scale (100 debug .. 600 emergency).
+    fn level_from_number(n: i64) -> LogLevel {
+        if n >= 100 {
+            match n {
+                ..=199 => LogLevel::Debug,
+                200..=249 => LogLevel::Info,
+                250..=299 => LogLevel::Notice,
+                300..=399 => LogLevel::Warning,
+                400..=499 => LogLevel::Error,
+                500..=549 => LogLevel::Critical,
+                550..=599 => LogLevel::Alert,
+                _ => LogLevel::Emergency,
+            }
+        } else {
+            match n {
+                ..=19 => LogLevel::Trace,
+                20..=29 => LogLevel::Debug,
+                30..=39 => LogLevel::Info,
+                40..=49 => LogLevel::Warning,
+                50..=59 => LogLevel::Error,
+                _ => LogLevel::Critical,
+            }
+        }
+    }
+
+    /// Parse a timestamp value, which may be a date string or an epoch number.
+    fn parse_timestamp(&self, value: &Value, timezone: &SourceTimezone) -> Option<DateTime<Utc>> {
+        match value {
+            Value::String(s) => timestamp::parse_iso8601_or_epoch(s, timezone),
+            Value::Number(n) => n.as_f64().and_then(timestamp::from_epoch),
+            _ => None,
+        }
+    }
+
+    /// Convert a JSON value to a display string.
+    fn value_to_string(value: Value) -> String {
+        match value {
+            Value::String(s) => s,
+            Value::Null => String::new(),
+            other => other.to_string(),
+        }
+    }
+}
+
+impl LogParser for JsonLinesParser {
+    fn name(&self) -> &'static str {
+        "JSON Lines"
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let mut object = self.parse_object(line)?;
+
+        let level = self
+            .take_first(&mut object, LEVEL_KEYS)
+            .and_then(|value| self.parse_level(&value, parse_context))
+            .unwrap_or_default();
+        // Monolog writes both "level" and "level_name"; drop the numeric duplicate.
+        object.remove("level");
+
+        let timestamp = self
+            .take_first(&mut object, TIMESTAMP_KEYS)
+            .and_then(|value| self.parse_timestamp(&value, &parse_context.timezone));
+        let message = self
+            .take_first(&mut object, MESSAGE_KEYS)
+            .map(Self::value_to_string)
+            .unwrap_or_default();
+        let channel = self
+            .take_first(&mut object, CHANNEL_KEYS)
+            .map(Self::value_to_string)
+            .filter(|c| !c.is_empty());
+
+        let context = if object.is_empty() {
+            None
+        } else {
+            Some(Value::Object(object))
+        };
+
+        Some(LogEntry::new(
+            format!("json-{}", line_number),
+            timestamp,
+            level,
+            message,
+            line.to_string(),
+            line_number,
+            context,
+            None,
+            channel,
+        ))
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        self.parse_object(line).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_pino_line() {
+        let parser = JsonLinesParser::new();
+        let line = r#"{"level":50,"time":1705314600000,"pid":42,"hostname":"web-1","msg":"Request failed"}"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "Request failed");
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T10:30:00+00:00"
+        );
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["pid"], 42);
+        assert_eq!(context["hostname"], "web-1");
+        assert!(context.get("msg").is_none());
+    }
+
+    #[test]
+    fn test_parse_monolog_json_formatter_line() {
+        let parser = JsonLinesParser::new();
+        let line = r#"{"message":"User logged in","context":{"user_id":123},"level":200,"level_name":"INFO","channel":"production","datetime":"2024-01-15T10:30:00.123456+01:00","extra":{}}"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Info);
+        assert_eq!(entry.message, "User logged in");
+        assert_eq!(entry.channel.as_deref(), Some("production"));
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T09:30:00.123456+00:00"
+        );
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["context"]["user_id"], 123);
+        assert!(context.get("level").is_none());
+    }
+
+    #[test]
+    fn test_parse_zap_line() {
+        let parser = JsonLinesParser::new();
+        let line = r#"{"level":"warn","ts":1705314600.5,"logger":"http","msg":"Slow request","latency":"2.1s"}"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Warning);
+        assert_eq!(entry.channel.as_deref(), Some("http"));
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T10:30:00.500+00:00"
+        );
+    }
+
+    #[test]
+    fn test_numeric_levels() {
+        let test_cases = [
+            (10, LogLevel::Trace),
+            (20, LogLevel::Debug),
+            (30, LogLevel::Info),
+            (40, LogLevel::Warning),
+            (50, LogLevel::Error),
+            (60, LogLevel::Critical),
+            (100, LogLevel::Debug),
+            (250, LogLevel::Notice),
+            (300, LogLevel::Warning),
+            (550, LogLevel::Alert),
+            (600, LogLevel::Emergency),
+        ];
+
+        for (number, expected_level) in test_cases {
+            assert_eq!(
+                JsonLinesParser::level_from_number(number),
+                expected_level,
+                "Failed for: {}",
+                number
+            );
+        }
+    }
+
+    #[test]
+    fn test_level_aliases() {
+        let parser = JsonLinesParser::new();
+        let parse_context = ParseContext {
+            level_aliases: [("3", LogLevel::Error), ("VERBOSE", LogLevel::Trace)]
+                .into_iter()
+                .collect(),
+            ..ParseContext::utc()
+        };
+
+        let entry = parser
+            .parse_with(r#"{"level":3,"msg":"Disk failure"}"#, 1, &parse_context)
+            .expect("Should parse log line");
+        assert_eq!(entry.level, LogLevel::Error);
+
+        let entry = parser
+            .parse_with(r#"{"level":"verbose","msg":"Polling"}"#, 2, &parse_context)
+            .expect("Should parse log line");
+        assert_eq!(entry.level, LogLevel::Trace);
+
+        let entry = parser
+            .parse_with(r#"{"level":30,"msg":"Unaliased"}"#, 3, &parse_context)
+            .expect("Should parse log line");
+        assert_eq!(entry.level, LogLevel::Info);
+    }
+
+    #[test]
+    fn test_alternative_keys() {
+        let parser = JsonLinesParser::new();
+        let line = r#"{"severity":"ERROR","@timestamp":"2024-01-15T10:30:00Z","message":"Boom"}"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "Boom");
+        assert!(entry.timestamp.is_some());
+        assert!(entry.context.is_none());
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let parser = JsonLinesParser::new();
+
+        assert!(parser.can_parse(r#"{"msg":"hello"}"#));
+        assert!(!parser.can_parse("[2024-01-15 10:30:00] local.ERROR: Test"));
+        assert!(!parser.can_parse(r#"["not", "an", "object"]"#));
+        assert!(!parser.can_parse("{ truncated"));
+    }
+}
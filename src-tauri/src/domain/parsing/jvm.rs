@@ -0,0 +1,275 @@
+//! JVM log parser for Logback and Log4j2.
+//!
+//! Parses the Spring Boot default console/file pattern:
+//! 2024-01-15 10:30:00.123 ERROR 1 --- [main] c.e.FooService : Message
+//!
+//! and the common Logback/Log4j2 pattern layouts:
+//! 2024-01-15 10:30:00,123 [main] ERROR com.example.FooService - Message
+//!
+//! Stack frames (`at ...`), `Caused by:` sections and `... N more` lines
+//! following an entry are attached as its stack trace.
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::sync::LazyLock;
+
+use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::SourceTimezone;
+
+use super::{timestamp, LogParser, ParseContext};
+
+/// Regex for the Spring Boot default log pattern.
+static SPRING_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4}-\d{2}-\d{2}[ T]\d{2}:\d{2}:\d{2}(?:[.,]\d{1,9})?(?:Z|[+-]\d{2}:?\d{2})?)\s+(TRACE|DEBUG|INFO|WARN|ERROR|FATAL)\s+(\d+) --- (?:\[\s*([^\]]*?)\s*\] )?\[\s*([^\]]*?)\s*\] (\S+)\s+: (.*)$")
+        .unwrap()
+});
+
+/// Regex for the Logback/Log4j2 `%d [%thread] %-5level %logger - %msg` pattern.
+static LAYOUT_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4}-\d{2}-\d{2}[ T]\d{2}:\d{2}:\d{2}(?:[.,]\d{1,9})?(?:Z|[+-]\d{2}:?\d{2})?|\d{2}:\d{2}:\d{2}[.,]\d{3}) \[([^\]]+)\] (TRACE|DEBUG|INFO|WARN|ERROR|FATAL)\s+(\S+) - (.*)$")
+        .unwrap()
+});
+
+/// Regex for exception lines (e.g. "Caused by: java.io.IOException: Broken pipe").
+static EXCEPTION_LINE_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(Caused by: )?((?:[a-zA-Z_$][\w$]*\.)+[A-Z][\w$]*)(?:: (.*))?$").unwrap()
+});
+
+/// JVM log parser for Logback and Log4j2.
+#[derive(Debug, Default, Clone)]
+pub struct JvmLogParser;
+
+impl JvmLogParser {
+    /// Create a new JVM log parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse the timestamp from a JVM log line.
+    ///
+    /// Time-only timestamps (the Logback default) have no date and are not parsed.
+    fn parse_timestamp(
+        &self,
+        timestamp_str: &str,
+        timezone: &SourceTimezone,
+    ) -> Option<DateTime<Utc>> {
+        if timestamp_str.len() <= 12 {
+            return None;
+        }
+        timestamp::parse_iso8601(&timestamp_str.replace(',', "."), timezone)
+    }
+
+    /// Extract the exception and its causes from stack trace lines.
+    fn extract_exceptions(&self, lines: &[String]) -> Vec<Value> {
+        lines
+            .iter()
+            .filter_map(|line| EXCEPTION_LINE_REGEX.captures(line.trim_end()))
+            .map(|captures| {
+                json!({
+                    "type": &captures[2],
+                    "message": captures.get(3).map(|m| m.as_str()).unwrap_or(""),
+                })
+            })
+            .collect()
+    }
+}
+
+impl LogParser for JvmLogParser {
+    fn name(&self) -> &'static str {
+        "JVM"
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let mut context = Map::new();
+
+        let (timestamp, level_str, message) = if let Some(captures) = SPRING_REGEX.captures(line) {
+            let pid: u32 = captures.get(3)?.as_str().parse().ok()?;
+            context.insert("pid".to_string(), json!(pid));
+            if let Some(application) = captures.get(4) {
+                context.insert("application".to_string(), json!(application.as_str()));
+            }
+            context.insert("thread".to_string(), json!(captures.get(5)?.as_str()));
+            context.insert("logger".to_string(), json!(captures.get(6)?.as_str()));
+            (
+                self.parse_timestamp(captures.get(1)?.as_str(), &parse_context.timezone),
+                captures.get(2)?.as_str(),
+                captures.get(7)?.as_str(),
+            )
+        } else {
+            let captures = LAYOUT_REGEX.captures(line)?;
+            context.insert("thread".to_string(), json!(captures.get(2)?.as_str()));
+            context.insert("logger".to_string(), json!(captures.get(4)?.as_str()));
+            (
+                self.parse_timestamp(captures.get(1)?.as_str(), &parse_context.timezone),
+                captures.get(3)?.as_str(),
+                captures.get(5)?.as_str(),
+            )
+        };
+
+        Some(LogEntry::new(
+            format!("jvm-{}", line_number),
+            timestamp,
+            parse_context.level(level_str),
+            message.trim_end().to_string(),
+            line.to_string(),
+            line_number,
+            Some(Value::Object(context)),
+            None,
+            None,
+        ))
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        SPRING_REGEX.is_match(line) || LAYOUT_REGEX.is_match(line)
+    }
+
+    fn parse_multiline_with(
+        &self,
+        lines: &[&str],
+        start_line: u64,
+        parse_context: &ParseContext,
+    ) -> Option<(LogEntry, usize)> {
+        let first_line = lines.first()?;
+        let mut entry = self.parse_with(first_line, start_line, parse_context)?;
+
+        // Collect exception lines, frames and "... N more" until the next entry
+        let mut stack_trace = Vec::new();
+        let mut consumed = 1;
+
+        for line in lines.iter().skip(1) {
+            if self.can_parse(line) || line.trim().is_empty() {
+                break;
+            }
+            stack_trace.push(line.to_string());
+            consumed += 1;
+        }
+
+        if stack_trace.is_empty() {
+            return Some((entry, consumed));
+        }
+
+        let mut exceptions = self.extract_exceptions(&stack_trace).into_iter();
+        if let (Some(exception), Some(Value::Object(context))) =
+            (exceptions.next(), entry.context.as_mut())
+        {
+            context.insert("exception".to_string(), exception);
+            let causes: Vec<Value> = exceptions.collect();
+            if !causes.is_empty() {
+                context.insert("caused_by".to_string(), Value::Array(causes));
+            }
+        }
+
+        Some((entry.with_stack_trace(stack_trace), consumed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::log_watching::log_level::LogLevel;
+
+    #[test]
+    fn test_parse_spring_boot_line() {
+        let parser = JvmLogParser::new();
+        let line = "2024-01-15 10:30:00.123 ERROR 1 --- [           main] c.e.FooService                           : Failed to start";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "Failed to start");
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T10:30:00.123+00:00"
+        );
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["pid"], 1);
+        assert_eq!(context["thread"], "main");
+        assert_eq!(context["logger"], "c.e.FooService");
+    }
+
+    #[test]
+    fn test_parse_spring_boot_3_line() {
+        let parser = JvmLogParser::new();
+        let line = "2024-01-15T10:30:00.123+01:00  INFO 42 --- [orders] [nio-8080-exec-1] o.s.web.servlet.DispatcherServlet        : Completed initialization";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Info);
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T09:30:00.123+00:00"
+        );
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["application"], "orders");
+        assert_eq!(context["thread"], "nio-8080-exec-1");
+    }
+
+    #[test]
+    fn test_parse_layout_lines() {
+        let parser = JvmLogParser::new();
+
+        let entry = parser
+            .parse(
+                "2024-01-15 10:30:00,123 [pool-1-thread-3] WARN  com.example.Job - Retrying",
+                1,
+            )
+            .expect("Should parse log line");
+        assert_eq!(entry.level, LogLevel::Warning);
+        assert_eq!(entry.message, "Retrying");
+        assert!(entry.timestamp.is_some());
+        assert_eq!(entry.context.unwrap()["thread"], "pool-1-thread-3");
+
+        let entry = parser
+            .parse("10:30:00.123 [main] TRACE c.e.Boot - Starting", 1)
+            .expect("Should parse log line");
+        assert_eq!(entry.level, LogLevel::Trace);
+        assert!(entry.timestamp.is_none());
+    }
+
+    #[test]
+    fn test_parse_multiline_with_caused_by() {
+        let parser = JvmLogParser::new();
+        let lines = vec![
+            "2024-01-15 10:30:00.123 ERROR 1 --- [main] c.e.FooService : Request failed",
+            "java.lang.IllegalStateException: Order not found",
+            "\tat com.example.FooService.load(FooService.java:42)",
+            "\tat com.example.FooController.get(FooController.java:12)",
+            "Caused by: java.sql.SQLException: Connection refused",
+            "\tat org.postgresql.Driver.connect(Driver.java:100)",
+            "\t... 2 more",
+            "2024-01-15 10:30:01.000  INFO 1 --- [main] c.e.FooService : Next entry",
+        ];
+
+        let (entry, consumed) = parser.parse_multiline(&lines, 1).expect("Should parse");
+
+        assert_eq!(consumed, 7);
+        assert_eq!(entry.stack_trace.as_ref().unwrap().len(), 6);
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(
+            context["exception"]["type"],
+            "java.lang.IllegalStateException"
+        );
+        assert_eq!(context["exception"]["message"], "Order not found");
+        assert_eq!(context["caused_by"][0]["type"], "java.sql.SQLException");
+        assert_eq!(context["logger"], "c.e.FooService");
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let parser = JvmLogParser::new();
+
+        assert!(parser.can_parse("2024-01-15 10:30:00.123 DEBUG 7 --- [main] a.B : hi"));
+        assert!(parser.can_parse("2024-01-15 10:30:00.123 [main] INFO a.B - hi"));
+        assert!(!parser.can_parse("\tat com.example.Foo.bar(Foo.java:1)"));
+        assert!(!parser.can_parse("[2024-01-15 10:30:00] local.ERROR: Test"));
+    }
+}
@@ -1,20 +1,30 @@
 //! Laravel log parser.
 //!
 //! Parses Laravel log format:
-//! [YYYY-MM-DD HH:MM:SS] environment.LEVEL: Message {"context": ...}
+//! [YYYY-MM-DD HH:MM:SS] environment.LEVEL: Message {"context": ...} {"extra": ...}
+//!
+//! Monolog appends the context and extra data as trailing JSON. Context
+//! holding serialised exceptions may continue over several lines; those
+//! exceptions are decomposed into class, code, message, origin and frames.
+//! This handling is shared with the other Monolog line formats through
+//! [`MonologLineFormat`].
 
-use chrono::{DateTime, NaiveDateTime, Utc};
+use chrono::{DateTime, Utc};
 use regex::Regex;
+use serde_json::Value;
 use std::sync::LazyLock;
 
 use crate::domain::log_watching::log_entry::LogEntry;
-use crate::domain::log_watching::log_level::LogLevel;
+use crate::domain::log_watching::{ExceptionInfo, SourceTimezone, StackFrame};
 
-use super::LogParser;
+use super::{timestamp, LogParser, ParseContext};
 
 /// Regex for parsing Laravel log lines.
 static LARAVEL_LOG_REGEX: LazyLock<Regex> = LazyLock::new(|| {
-    Regex::new(r"^\[(\d{4}-\d{2}-\d{2}\s+\d{2}:\d{2}:\d{2})\]\s+(\w+)\.(\w+):\s*(.*)$").unwrap()
+    Regex::new(
+        r"^\[(\d{4}-\d{2}-\d{2}[T ]\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?)\]\s+(\w+)\.(\w+):\s*(.*)$",
+    )
+    .unwrap()
 });
 
 /// Regex for detecting stack trace lines.
@@ -24,85 +34,244 @@ static STACK_TRACE_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^#\d+\
 static CONTINUATION_REGEX: LazyLock<Regex> =
     LazyLock::new(|| Regex::new(r"^\s+(?:at|in|thrown)\s+").unwrap());
 
-/// Laravel log parser.
-#[derive(Debug, Default, Clone)]
-pub struct LaravelLogParser;
+/// Regex for the line closing a multi-line context, e.g. `"} []`.
+static CONTEXT_END_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"^"[}\]]+(?:\s+(?:\[\]|\{.*\}))?\s*$"#).unwrap());
+
+/// Regex for a serialised exception header:
+/// [object] (App\Exceptions\FooException(code: 0): Message at /path/File.php:42)
+static EXCEPTION_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"(?s)^\[object\] \((?P<class>[^\s(]+)\(code: (?P<code>[^)]*)\): (?P<message>.*) at (?P<file>[^\n]+?):(?P<line>\d+)\)$",
+    )
+    .unwrap()
+});
 
-impl LaravelLogParser {
-    /// Create a new Laravel log parser.
-    pub fn new() -> Self {
-        Self
+/// Monolog context and extra data split from a message.
+#[derive(Debug, Default, PartialEq)]
+struct TrailingJson {
+    message: String,
+    context: Option<Value>,
+    extra: Option<Value>,
+}
+
+/// A Monolog `LineFormatter` layout: `[timestamp] channel.LEVEL: message context extra`.
+#[derive(Debug, Clone, Copy)]
+pub(super) struct MonologLineFormat {
+    /// Regex capturing the timestamp, channel, level and message.
+    regex: &'static LazyLock<Regex>,
+    /// Prefix of the IDs of parsed entries.
+    id_prefix: &'static str,
+}
+
+impl MonologLineFormat {
+    /// Create a format from its line regex and entry ID prefix.
+    pub(super) fn new(regex: &'static LazyLock<Regex>, id_prefix: &'static str) -> Self {
+        Self { regex, id_prefix }
     }
 
-    /// Parse the timestamp from a Laravel log line.
-    fn parse_timestamp(&self, timestamp_str: &str) -> Option<DateTime<Utc>> {
-        NaiveDateTime::parse_from_str(timestamp_str, "%Y-%m-%d %H:%M:%S")
-            .ok()
-            .map(|dt| dt.and_utc())
-    }
-
-    /// Parse context JSON from the message if present.
-    fn extract_context(&self, message: &str) -> (String, Option<serde_json::Value>) {
-        // Look for JSON at the end of the message
-        if let Some(json_start) = message.rfind(" {") {
-            let json_str = &message[json_start + 1..];
-            if json_str.ends_with('}') {
-                if let Ok(context) = serde_json::from_str(json_str) {
-                    let clean_message = message[..json_start].trim().to_string();
-                    return (clean_message, Some(context));
+    /// Parse the timestamp from a Monolog log line.
+    ///
+    /// Laravel's format has no offset; other date formats may add fractional
+    /// seconds and an offset, which is kept when present.
+    fn parse_timestamp(
+        &self,
+        timestamp_str: &str,
+        timezone: &SourceTimezone,
+    ) -> Option<DateTime<Utc>> {
+        timestamp::parse_iso8601(timestamp_str, timezone)
+    }
+
+    /// Split trailing context and extra JSON from the message, if present.
+    fn extract_context(&self, message: &str) -> TrailingJson {
+        Self::split_trailing_json(message).unwrap_or_else(|| TrailingJson {
+            message: message.to_string(),
+            ..TrailingJson::default()
+        })
+    }
+
+    /// Find the leftmost position from which the rest of the text is one or
+    /// two JSON objects or arrays (Monolog's context and extra).
+    ///
+    /// A single value is treated as context. Empty `[]` and `{}` values are
+    /// dropped.
+    fn split_trailing_json(text: &str) -> Option<TrailingJson> {
+        let text = text.trim_end();
+        if !text.ends_with(['}', ']']) {
+            return None;
+        }
+
+        let candidates = text.char_indices().filter(|&(i, c)| {
+            matches!(c, '{' | '[')
+                && text[..i]
+                    .chars()
+                    .next_back()
+                    .map_or(true, char::is_whitespace)
+        });
+
+        for (start, _) in candidates {
+            let mut values = Vec::new();
+            let stream = serde_json::Deserializer::from_str(&text[start..]).into_iter::<Value>();
+            let valid = stream.into_iter().all(|value| match value {
+                Ok(value @ (Value::Object(_) | Value::Array(_))) => {
+                    values.push(value);
+                    values.len() <= 2
                 }
+                _ => false,
+            });
+            if !valid || values.is_empty() {
+                continue;
             }
+
+            let non_empty = |value: Value| match &value {
+                Value::Object(map) if map.is_empty() => None,
+                Value::Array(items) if items.is_empty() => None,
+                _ => Some(value),
+            };
+            let mut values = values.into_iter();
+            return Some(TrailingJson {
+                message: text[..start].trim().to_string(),
+                context: values.next().and_then(non_empty),
+                extra: values.next().and_then(non_empty),
+            });
         }
-        (message.to_string(), None)
+
+        None
     }
 
-    /// Check if a line is part of a stack trace.
-    fn is_stack_trace_line(&self, line: &str) -> bool {
-        STACK_TRACE_REGEX.is_match(line)
-            || CONTINUATION_REGEX.is_match(line)
-            || line.trim().starts_with("Stack trace:")
-            || line.trim().starts_with("[stacktrace]")
+    /// Split context that continues over the following lines, as written
+    /// for serialised exceptions.
+    ///
+    /// Monolog writes these line breaks raw inside JSON strings, so they are
+    /// escaped before parsing. The JSON must start on the first line.
+    fn extract_multiline_context(
+        &self,
+        message: &str,
+        following: &[String],
+    ) -> Option<TrailingJson> {
+        let mut text = message.to_string();
+        for line in following {
+            text.push_str("\\n");
+            text.push_str(&line.replace('\t', "\\t"));
+        }
+
+        Self::split_trailing_json(&text)
+            .filter(|trailing| trailing.message.len() <= message.trim().len())
     }
-}
 
-impl LogParser for LaravelLogParser {
-    fn name(&self) -> &'static str {
-        "Laravel"
+    /// Decompose a serialised exception from the context, following
+    /// `[previous exception]` sections into the chain of causes.
+    fn parse_exception(payload: &str) -> Option<ExceptionInfo> {
+        let mut exceptions = payload
+            .split("\n[previous exception] ")
+            .map(Self::parse_single_exception)
+            .collect::<Option<Vec<_>>>()?;
+
+        let mut chained: Option<ExceptionInfo> = None;
+        while let Some(mut exception) = exceptions.pop() {
+            exception.previous = chained.map(Box::new);
+            chained = Some(exception);
+        }
+        chained
     }
 
-    fn parse(&self, line: &str, line_number: u64) -> Option<LogEntry> {
-        let captures = LARAVEL_LOG_REGEX.captures(line)?;
+    /// Decompose one exception header and its `[stacktrace]` block.
+    fn parse_single_exception(section: &str) -> Option<ExceptionInfo> {
+        let (header, trace) = section
+            .split_once("\n[stacktrace]\n")
+            .unwrap_or((section, ""));
+        let captures = EXCEPTION_REGEX.captures(header.trim())?;
+        let trace: Vec<String> = trace.lines().map(str::to_string).collect();
+
+        Some(ExceptionInfo {
+            class: captures["class"].to_string(),
+            code: Some(captures["code"].trim().to_string()).filter(|c| !c.is_empty()),
+            message: captures["message"].to_string(),
+            file: Some(captures["file"].to_string()),
+            line: captures["line"].parse().ok(),
+            frames: StackFrame::parse_all(&trace),
+            previous: None,
+        })
+    }
 
+    /// Build an entry from a parsed header and the split message.
+    fn build_entry(
+        &self,
+        captures: &regex::Captures,
+        trailing: TrailingJson,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
         let timestamp_str = captures.get(1)?.as_str();
         let environment = captures.get(2)?.as_str();
         let level_str = captures.get(3)?.as_str();
-        let message = captures.get(4)?.as_str();
 
-        let timestamp = self.parse_timestamp(timestamp_str);
-        let level = LogLevel::parse(level_str);
-        let (clean_message, context) = self.extract_context(message);
-
-        Some(LogEntry::new(
-            format!("laravel-{}", line_number),
-            timestamp,
-            level,
-            clean_message,
+        let exception = trailing
+            .context
+            .as_ref()
+            .and_then(|context| context.get("exception"))
+            .and_then(Value::as_str)
+            .and_then(Self::parse_exception);
+
+        let mut entry = LogEntry::new(
+            format!("{}-{}", self.id_prefix, line_number),
+            self.parse_timestamp(timestamp_str, &parse_context.timezone),
+            parse_context.level(level_str),
+            trailing.message,
             line.to_string(),
             line_number,
-            context,
+            trailing.context,
             None,
             Some(environment.to_string()),
-        ))
+        );
+
+        if let Some(extra) = trailing.extra {
+            entry = entry.with_extra(extra);
+        }
+        if let Some(exception) = exception {
+            entry = entry.with_exception(exception);
+        }
+        Some(entry)
     }
 
-    fn can_parse(&self, line: &str) -> bool {
-        LARAVEL_LOG_REGEX.is_match(line)
+    /// Check if a line is part of a stack trace.
+    fn is_stack_trace_line(&self, line: &str) -> bool {
+        STACK_TRACE_REGEX.is_match(line)
+            || CONTINUATION_REGEX.is_match(line)
+            || line.trim().starts_with("Stack trace:")
+            || line.trim().starts_with("[stacktrace]")
+    }
+
+    /// Parse a single line.
+    pub(super) fn parse(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let captures = self.regex.captures(line)?;
+        let trailing = self.extract_context(captures.get(4)?.as_str());
+
+        self.build_entry(&captures, trailing, line, line_number, parse_context)
+    }
+
+    /// Check if a line starts an entry.
+    pub(super) fn can_parse(&self, line: &str) -> bool {
+        self.regex.is_match(line)
     }
 
-    fn parse_multiline(&self, lines: &[&str], start_line: u64) -> Option<(LogEntry, usize)> {
+    /// Parse the entry starting at the first line, with its stack trace and
+    /// any context continuing below.
+    pub(super) fn parse_multiline(
+        &self,
+        lines: &[&str],
+        start_line: u64,
+        parse_context: &ParseContext,
+    ) -> Option<(LogEntry, usize)> {
         // Parse the first line
         let first_line = lines.first()?;
-        let mut entry = self.parse(first_line, start_line)?;
+        let mut entry = self.parse(first_line, start_line, parse_context)?;
 
         // Collect stack trace lines
         let mut stack_trace = Vec::new();
@@ -125,6 +294,26 @@ impl LogParser for LaravelLogParser {
             }
         }
 
+        if stack_trace.is_empty() {
+            return Some((entry, consumed));
+        }
+
+        // Context without a closing brace on the first line continues below
+        if entry.context.is_none() && entry.extra.is_none() {
+            let captures = self.regex.captures(first_line)?;
+            let message = captures.get(4)?.as_str();
+            if let Some(trailing) = self.extract_multiline_context(message, &stack_trace) {
+                entry =
+                    self.build_entry(&captures, trailing, first_line, start_line, parse_context)?;
+                if stack_trace
+                    .last()
+                    .is_some_and(|line| CONTEXT_END_REGEX.is_match(line.trim()))
+                {
+                    stack_trace.pop();
+                }
+            }
+        }
+
         if !stack_trace.is_empty() {
             entry = entry.with_stack_trace(stack_trace);
         }
@@ -133,6 +322,51 @@ impl LogParser for LaravelLogParser {
     }
 }
 
+/// Laravel log parser.
+#[derive(Debug, Default, Clone)]
+pub struct LaravelLogParser;
+
+impl LaravelLogParser {
+    /// Create a new Laravel log parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The Laravel line layout.
+    fn format(&self) -> MonologLineFormat {
+        MonologLineFormat::new(&LARAVEL_LOG_REGEX, "laravel")
+    }
+}
+
+impl LogParser for LaravelLogParser {
+    fn name(&self) -> &'static str {
+        "Laravel"
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        self.format().parse(line, line_number, parse_context)
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        self.format().can_parse(line)
+    }
+
+    fn parse_multiline_with(
+        &self,
+        lines: &[&str],
+        start_line: u64,
+        parse_context: &ParseContext,
+    ) -> Option<(LogEntry, usize)> {
+        self.format()
+            .parse_multiline(lines, start_line, parse_context)
+    }
+}
+
 /// Detects Laravel daily log files in a directory.
 #[derive(Debug, Default)]
 pub struct LaravelDailyLogDetector;
@@ -216,6 +450,7 @@ impl LaravelDailyLogDetector {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::domain::log_watching::log_level::LogLevel;
 
     #[test]
     fn test_parse_basic_log_line() {
@@ -229,6 +464,40 @@ mod tests {
         assert!(entry.timestamp.is_some());
     }
 
+    #[test]
+    fn test_parse_timestamp_in_source_timezone() {
+        let parser = LaravelLogParser::new();
+        let parse_context = ParseContext {
+            timezone: "Europe/Oslo".parse().unwrap(),
+            ..ParseContext::default()
+        };
+
+        let naive = parser
+            .parse_with(
+                "[2024-07-15 10:30:00] local.INFO: Summer",
+                1,
+                &parse_context,
+            )
+            .expect("Should parse log line");
+        let explicit = parser
+            .parse_with(
+                "[2024-07-15T10:30:00.123456+00:00] local.INFO: Offset",
+                2,
+                &parse_context,
+            )
+            .expect("Should parse log line");
+
+        assert_eq!(
+            naive.timestamp.unwrap().to_rfc3339(),
+            "2024-07-15T08:30:00+00:00"
+        );
+        assert_eq!(
+            explicit.timestamp.unwrap().to_rfc3339(),
+            "2024-07-15T10:30:00.123456+00:00"
+        );
+        assert_eq!(explicit.message, "Offset");
+    }
+
     #[test]
     fn test_parse_log_with_context() {
         let parser = LaravelLogParser::new();
@@ -241,6 +510,146 @@ mod tests {
         assert!(entry.context.is_some());
     }
 
+    #[test]
+    fn test_split_context_and_extra() {
+        let parser = LaravelLogParser::new();
+        let line = r#"[2024-01-15 10:30:00] local.INFO: Matched {id} in [a, b] {"user":{"id":1,"tags":["x"]}} {"request_id":"abc"}"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.message, "Matched {id} in [a, b]");
+        assert_eq!(entry.context.unwrap()["user"]["tags"][0], "x");
+        assert_eq!(entry.extra.unwrap()["request_id"], "abc");
+    }
+
+    #[test]
+    fn test_empty_context_and_extra_are_dropped() {
+        let parser = LaravelLogParser::new();
+        let test_cases = [
+            ("[2024-01-15 10:30:00] local.INFO: Started [] []", "Started"),
+            (
+                r#"[2024-01-15 10:30:00] local.INFO: Body was {"a":1} [] []"#,
+                r#"Body was {"a":1}"#,
+            ),
+            (
+                "[2024-01-15 10:30:00] local.INFO: Count {x} ends }",
+                "Count {x} ends }",
+            ),
+        ];
+
+        for (line, expected_message) in test_cases {
+            let entry = parser.parse(line, 1).expect("Should parse log line");
+            assert_eq!(entry.message, expected_message, "Failed for: {}", line);
+            assert!(entry.context.is_none(), "Failed for: {}", line);
+            assert!(entry.extra.is_none(), "Failed for: {}", line);
+        }
+
+        let entry = parser
+            .parse(
+                r#"[2024-01-15 10:30:00] local.INFO: Done [] {"memory":"12MB"}"#,
+                1,
+            )
+            .expect("Should parse log line");
+        assert!(entry.context.is_none());
+        assert_eq!(entry.extra.unwrap()["memory"], "12MB");
+    }
+
+    #[test]
+    fn test_parse_multiline_context() {
+        let parser = LaravelLogParser::new();
+        let lines = vec![
+            r#"[2024-01-15 10:30:00] local.ERROR: Division by zero {"userId":5,"exception":"[object] (DivisionByZeroError(code: 0): Division by zero at /app/Calc.php:12)"#,
+            "[stacktrace]",
+            r"#0 /app/Http/Controller.php(20): App\\Calc->divide()",
+            "#1 {main}",
+            r#""} {"url":"/calc"}"#,
+            "[2024-01-15 10:30:01] local.INFO: Next entry",
+        ];
+
+        let (entry, consumed) = parser.parse_multiline(&lines, 1).expect("Should parse");
+
+        assert_eq!(consumed, 5);
+        assert_eq!(entry.message, "Division by zero");
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["userId"], 5);
+        assert!(context["exception"]
+            .as_str()
+            .unwrap()
+            .ends_with("#1 {main}\n"));
+        assert_eq!(entry.extra.unwrap()["url"], "/calc");
+        assert_eq!(
+            entry.stack_trace.unwrap(),
+            vec![
+                "[stacktrace]",
+                r"#0 /app/Http/Controller.php(20): App\\Calc->divide()",
+                "#1 {main}"
+            ]
+        );
+        assert_eq!(entry.stack_frames.len(), 2);
+
+        let exception = entry.exception.expect("Should have exception");
+        assert_eq!(exception.class, "DivisionByZeroError");
+        assert_eq!(exception.code.as_deref(), Some("0"));
+        assert_eq!(exception.message, "Division by zero");
+        assert_eq!(exception.file.as_deref(), Some("/app/Calc.php"));
+        assert_eq!(exception.line, Some(12));
+        assert_eq!(exception.frames.len(), 2);
+        assert_eq!(exception.frames[0].class.as_deref(), Some("App\\Calc"));
+        assert_eq!(exception.frames[0].line, Some(20));
+    }
+
+    #[test]
+    fn test_parse_exception_chain() {
+        let payload = "[object] (Illuminate\\Database\\QueryException(code: 42S02): Table missing (SQL: select * from users) at /app/vendor/Connection.php:760)\n[stacktrace]\n#0 /app/app/Repo.php(15): Illuminate\\Database\\Connection->select()\n#1 {main}\n\n[previous exception] [object] (PDOException(code: 42S02): SQLSTATE[42S02]: Base table not found at /app/vendor/Connection.php:416)\n[stacktrace]\n#0 {main}\n";
+
+        let exception =
+            MonologLineFormat::parse_exception(payload).expect("Should parse exception");
+
+        assert_eq!(exception.class, "Illuminate\\Database\\QueryException");
+        assert_eq!(exception.code.as_deref(), Some("42S02"));
+        assert_eq!(
+            exception.message,
+            "Table missing (SQL: select * from users)"
+        );
+        assert_eq!(exception.line, Some(760));
+        assert_eq!(exception.frames.len(), 2);
+        assert_eq!(
+            exception.frames[0].file.as_deref(),
+            Some("/app/app/Repo.php")
+        );
+
+        let previous = exception.previous.expect("Should have previous exception");
+        assert_eq!(previous.class, "PDOException");
+        assert_eq!(previous.message, "SQLSTATE[42S02]: Base table not found");
+        assert_eq!(previous.line, Some(416));
+        assert!(previous.previous.is_none());
+    }
+
+    #[test]
+    fn test_parse_single_line_exception() {
+        let parser = LaravelLogParser::new();
+        let line = r#"[2024-01-15 10:30:00] production.ERROR: Not found {"exception":"[object] (Symfony\\Component\\HttpKernel\\Exception\\NotFoundHttpException(code: 0):  at /app/vendor/Router.php:44)"}"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        let exception = entry.exception.expect("Should have exception");
+        assert_eq!(
+            exception.class,
+            "Symfony\\Component\\HttpKernel\\Exception\\NotFoundHttpException"
+        );
+        assert_eq!(exception.message, "");
+        assert_eq!(exception.line, Some(44));
+        assert!(exception.frames.is_empty());
+        assert!(parser
+            .parse(
+                "[2024-01-15 10:30:00] local.ERROR: Plain {\"exception\":\"oops\"}",
+                1
+            )
+            .unwrap()
+            .exception
+            .is_none());
+    }
+
     #[test]
     fn test_parse_different_levels() {
         let parser = LaravelLogParser::new();
@@ -0,0 +1,238 @@
+//! logfmt log parser.
+//!
+//! Parses key=value structured logs as written by Go's log/slog, logrus,
+//! go-kit and Heroku-style apps:
+//! time=2024-01-15T10:30:00Z level=warn msg="Slow request" user_id=42
+
+use serde_json::{Map, Value};
+
+use crate::domain::log_watching::log_entry::LogEntry;
+
+use super::{timestamp, LogParser, ParseContext};
+
+/// Keys that may hold the log level, in order of preference.
+const LEVEL_KEYS: &[&str] = &["level", "lvl", "severity"];
+
+/// Keys that may hold the timestamp, in order of preference.
+const TIMESTAMP_KEYS: &[&str] = &["time", "ts", "timestamp", "t"];
+
+/// Keys that may hold the message, in order of preference.
+const MESSAGE_KEYS: &[&str] = &["msg", "message"];
+
+/// Keys that may hold the channel or logger name, in order of preference.
+const CHANNEL_KEYS: &[&str] = &["logger", "channel", "component"];
+
+/// logfmt log parser.
+#[derive(Debug, Default, Clone)]
+pub struct LogfmtParser;
+
+impl LogfmtParser {
+    /// Create a new logfmt parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Split a line into key/value pairs.
+    ///
+    /// Returns None if the line is not valid logfmt. Bare keys without a
+    /// value are returned with a None value.
+    fn tokenize(&self, line: &str) -> Option<Vec<(String, Option<String>)>> {
+        let mut pairs = Vec::new();
+        let mut chars = line.trim().chars().peekable();
+
+        loop {
+            while chars.next_if(|c| c.is_whitespace()).is_some() {}
+            if chars.peek().is_none() {
+                break;
+            }
+
+            let mut key = String::new();
+            while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '=') {
+                if c == '"' {
+                    return None;
+                }
+                key.push(c);
+            }
+            if key.is_empty() {
+                return None;
+            }
+
+            if chars.next_if_eq(&'=').is_none() {
+                pairs.push((key, None));
+                continue;
+            }
+
+            let mut value = String::new();
+            if chars.next_if_eq(&'"').is_some() {
+                let mut closed = false;
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => {
+                            closed = true;
+                            break;
+                        }
+                        '\\' => match chars.next()? {
+                            'n' => value.push('\n'),
+                            't' => value.push('\t'),
+                            'r' => value.push('\r'),
+                            other => value.push(other),
+                        },
+                        other => value.push(other),
+                    }
+                }
+                if !closed {
+                    return None;
+                }
+            } else {
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    value.push(c);
+                }
+            }
+            pairs.push((key, Some(value)));
+        }
+
+        Some(pairs)
+    }
+
+    /// Remove and return the value of the first key present in the pairs.
+    fn take_first(
+        &self,
+        pairs: &mut Vec<(String, Option<String>)>,
+        keys: &[&str],
+    ) -> Option<String> {
+        keys.iter().find_map(|key| {
+            let index = pairs.iter().position(|(k, v)| k == key && v.is_some())?;
+            pairs.remove(index).1
+        })
+    }
+
+    /// Check whether the pairs contain any well-known key with a value.
+    fn has_known_key(&self, pairs: &[(String, Option<String>)]) -> bool {
+        pairs.iter().any(|(key, value)| {
+            value.is_some()
+                && (LEVEL_KEYS.contains(&key.as_str()) || MESSAGE_KEYS.contains(&key.as_str()))
+        })
+    }
+}
+
+impl LogParser for LogfmtParser {
+    fn name(&self) -> &'static str {
+        "logfmt"
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let mut pairs = self.tokenize(line)?;
+        if !self.has_known_key(&pairs) {
+            return None;
+        }
+
+        let level = self
+            .take_first(&mut pairs, LEVEL_KEYS)
+            .map(|s| parse_context.level(&s))
+            .unwrap_or_default();
+        let timestamp = self
+            .take_first(&mut pairs, TIMESTAMP_KEYS)
+            .and_then(|s| timestamp::parse_iso8601_or_epoch(&s, &parse_context.timezone));
+        let message = self
+            .take_first(&mut pairs, MESSAGE_KEYS)
+            .unwrap_or_default();
+        let channel = self
+            .take_first(&mut pairs, CHANNEL_KEYS)
+            .filter(|c| !c.is_empty());
+
+        let context = if pairs.is_empty() {
+            None
+        } else {
+            let map: Map<String, Value> = pairs
+                .into_iter()
+                .map(|(key, value)| (key, value.map(Value::String).unwrap_or(Value::Bool(true))))
+                .collect();
+            Some(Value::Object(map))
+        };
+
+        Some(LogEntry::new(
+            format!("logfmt-{}", line_number),
+            timestamp,
+            level,
+            message,
+            line.to_string(),
+            line_number,
+            context,
+            None,
+            channel,
+        ))
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        self.tokenize(line)
+            .is_some_and(|pairs| self.has_known_key(&pairs))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::log_watching::log_level::LogLevel;
+
+    #[test]
+    fn test_parse_basic_line() {
+        let parser = LogfmtParser::new();
+        let line = r#"time=2024-01-15T10:30:00Z level=warn msg="Slow request" user_id=42"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Warning);
+        assert_eq!(entry.message, "Slow request");
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T10:30:00+00:00"
+        );
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["user_id"], "42");
+        assert!(context.get("msg").is_none());
+    }
+
+    #[test]
+    fn test_parse_quoted_values_with_escapes() {
+        let parser = LogfmtParser::new();
+        let line = r#"level=error msg="said \"hi\" and left\nbye" path="C:\\logs" empty="""#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "said \"hi\" and left\nbye");
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["path"], r"C:\logs");
+        assert_eq!(context["empty"], "");
+    }
+
+    #[test]
+    fn test_bare_keys_are_true() {
+        let parser = LogfmtParser::new();
+        let line = "level=info msg=started debug component=worker";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.channel.as_deref(), Some("worker"));
+        assert_eq!(entry.context.expect("Should have context")["debug"], true);
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let parser = LogfmtParser::new();
+
+        assert!(parser.can_parse("level=info msg=hello"));
+        assert!(parser.can_parse(r#"msg="only a message""#));
+        assert!(!parser.can_parse("Server started on port 8080"));
+        assert!(!parser.can_parse("user_id=42 action=login"));
+        assert!(!parser.can_parse(r#"level=info msg="unterminated"#));
+        assert!(!parser.can_parse("[2024-01-15 10:30:00] local.ERROR: a=b"));
+    }
+}
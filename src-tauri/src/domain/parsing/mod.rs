@@ -2,8 +2,45 @@
 //!
 //! This module contains parsers for various log formats.
 
+mod access_log;
+mod ansi;
+mod apache_error;
+mod container;
+mod context;
+mod custom;
+mod detection;
+mod fallback;
+mod generic;
+mod grok;
+mod json_lines;
+mod jvm;
 mod laravel;
+mod logfmt;
+mod nginx_error;
+mod python;
+mod rails;
+mod symfony;
+mod syslog;
+mod timestamp;
 mod traits;
 
+pub use access_log::AccessLogParser;
+pub use ansi::AnsiStripper;
+pub use apache_error::ApacheErrorLogParser;
+pub use container::{CriLogParser, DockerJsonLogParser, PayloadParsers};
+pub use context::ParseContext;
+pub use custom::{CustomParserDefinition, CustomParserError, CustomRegexParser, PatternSyntax};
+pub use detection::FormatDetector;
+pub use fallback::FallbackParser;
+pub use generic::GenericParser;
+pub use grok::{FieldType, GrokError, GrokLibrary, GrokPattern};
+pub use json_lines::JsonLinesParser;
+pub use jvm::JvmLogParser;
 pub use laravel::{LaravelDailyLogDetector, LaravelLogParser};
+pub use logfmt::LogfmtParser;
+pub use nginx_error::NginxErrorLogParser;
+pub use python::PythonLogParser;
+pub use rails::RailsLogParser;
+pub use symfony::SymfonyLogParser;
+pub use syslog::SyslogParser;
 pub use traits::LogParser;
@@ -0,0 +1,204 @@
+//! Nginx error log parser.
+//!
+//! Parses the Nginx error log format:
+//! 2024/01/15 10:30:00 [error] 1234#0: *5 open() "/x" failed, client: 127.0.0.1, server: localhost
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::sync::LazyLock;
+
+use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::SourceTimezone;
+
+use super::{LogParser, ParseContext};
+
+/// Regex for parsing Nginx error log lines.
+static NGINX_ERROR_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4}/\d{2}/\d{2} \d{2}:\d{2}:\d{2}) \[(\w+)\] (\d+)#(\d+): (?:\*(\d+) )?(.*)$")
+        .unwrap()
+});
+
+/// Regex for the trailing `, key: value` details Nginx appends to messages.
+static DETAIL_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r#", (client|server|request|upstream|host|referrer): ("(?:[^"\\]|\\.)*"|[^,]*)"#)
+        .unwrap()
+});
+
+/// Nginx error log parser.
+#[derive(Debug, Default, Clone)]
+pub struct NginxErrorLogParser;
+
+impl NginxErrorLogParser {
+    /// Create a new Nginx error log parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse the timestamp from an Nginx error log line.
+    fn parse_timestamp(
+        &self,
+        timestamp_str: &str,
+        timezone: &SourceTimezone,
+    ) -> Option<DateTime<Utc>> {
+        NaiveDateTime::parse_from_str(timestamp_str, "%Y/%m/%d %H:%M:%S")
+            .ok()
+            .map(|dt| timezone.to_utc(dt))
+    }
+
+    /// Split the trailing request details from the message.
+    fn extract_details(&self, message: &str) -> (String, Map<String, Value>) {
+        let mut details = Map::new();
+        let Some(first) = DETAIL_REGEX.find(message) else {
+            return (message.to_string(), details);
+        };
+
+        for captures in DETAIL_REGEX.captures_iter(&message[first.start()..]) {
+            let key = &captures[1];
+            let value = captures[2].trim_matches('"');
+            details.insert(key.to_string(), json!(value));
+        }
+
+        (message[..first.start()].to_string(), details)
+    }
+}
+
+impl LogParser for NginxErrorLogParser {
+    fn name(&self) -> &'static str {
+        "Nginx Error"
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let captures = NGINX_ERROR_REGEX.captures(line)?;
+
+        let timestamp_str = captures.get(1)?.as_str();
+        let level_str = captures.get(2)?.as_str();
+        let pid = captures.get(3).and_then(|m| m.as_str().parse::<u32>().ok());
+        let tid = captures.get(4).and_then(|m| m.as_str().parse::<u64>().ok());
+        let connection_id = captures.get(5).and_then(|m| m.as_str().parse::<u64>().ok());
+        let message = captures.get(6)?.as_str();
+
+        let (clean_message, details) = self.extract_details(message);
+
+        let mut context = Map::new();
+        if let Some(pid) = pid {
+            context.insert("pid".to_string(), json!(pid));
+        }
+        if let Some(tid) = tid {
+            context.insert("tid".to_string(), json!(tid));
+        }
+        if let Some(connection_id) = connection_id {
+            context.insert("connection_id".to_string(), json!(connection_id));
+        }
+        context.extend(details);
+
+        Some(LogEntry::new(
+            format!("nginx-error-{}", line_number),
+            self.parse_timestamp(timestamp_str, &parse_context.timezone),
+            parse_context.level(level_str),
+            clean_message,
+            line.to_string(),
+            line_number,
+            Some(Value::Object(context)),
+            None,
+            None,
+        ))
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        NGINX_ERROR_REGEX.is_match(line)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::log_watching::log_level::LogLevel;
+
+    #[test]
+    fn test_parse_error_with_client_details() {
+        let parser = NginxErrorLogParser::new();
+        let line = r#"2024/01/15 10:30:00 [error] 1234#0: *5 open() "/var/www/favicon.ico" failed (2: No such file or directory), client: 127.0.0.1, server: localhost, request: "GET /favicon.ico HTTP/1.1", host: "localhost""#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(
+            entry.message,
+            r#"open() "/var/www/favicon.ico" failed (2: No such file or directory)"#
+        );
+        assert!(entry.timestamp.is_some());
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["pid"], 1234);
+        assert_eq!(context["tid"], 0);
+        assert_eq!(context["connection_id"], 5);
+        assert_eq!(context["client"], "127.0.0.1");
+        assert_eq!(context["server"], "localhost");
+        assert_eq!(context["request"], "GET /favicon.ico HTTP/1.1");
+        assert_eq!(context["host"], "localhost");
+    }
+
+    #[test]
+    fn test_parse_without_connection() {
+        let parser = NginxErrorLogParser::new();
+        let line = "2024/01/15 10:30:00 [notice] 1#1: start worker processes";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Notice);
+        assert_eq!(entry.message, "start worker processes");
+
+        let context = entry.context.expect("Should have context");
+        assert!(context.get("connection_id").is_none());
+    }
+
+    #[test]
+    fn test_parse_with_overflowing_ids() {
+        let parser = NginxErrorLogParser::new();
+        let line = "2024/01/15 10:30:00 [error] 99999999999#184467440737095516160: worker crashed";
+
+        let entry = parser.parse(line, 1).expect("Should still parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "worker crashed");
+
+        let context = entry.context.expect("Should have context");
+        assert!(context.get("pid").is_none());
+        assert!(context.get("tid").is_none());
+    }
+
+    #[test]
+    fn test_parse_different_levels() {
+        let parser = NginxErrorLogParser::new();
+
+        let test_cases = [
+            ("debug", LogLevel::Debug),
+            ("info", LogLevel::Info),
+            ("warn", LogLevel::Warning),
+            ("crit", LogLevel::Critical),
+            ("alert", LogLevel::Alert),
+            ("emerg", LogLevel::Emergency),
+        ];
+
+        for (level, expected_level) in test_cases {
+            let line = format!("2024/01/15 10:30:00 [{}] 1#0: message", level);
+            let entry = parser.parse(&line, 1).expect("Should parse log line");
+            assert_eq!(entry.level, expected_level, "Failed for: {}", line);
+        }
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let parser = NginxErrorLogParser::new();
+
+        assert!(parser.can_parse("2024/01/15 10:30:00 [warn] 12#12: *1 upstream timed out"));
+        assert!(!parser.can_parse("[2024-01-15 10:30:00] local.ERROR: Test"));
+        assert!(!parser.can_parse("2024-01-15 10:30:00 [error] 1#0: wrong date"));
+    }
+}
@@ -0,0 +1,350 @@
+//! Python logging parser.
+//!
+//! Parses the common Python `logging` formats:
+//! - `2024-01-15 10:30:00,123 - myapp.views - ERROR - Message`
+//! - `2024-01-15 10:30:00,123 ERROR django.request: Message`
+//! - `[2024-01-15 10:30:00,123: ERROR/MainProcess] Message` (Celery)
+//! - `ERROR:django.request:Message` (`logging.basicConfig` default)
+//!
+//! Tracebacks following an entry are attached as its stack trace, and the
+//! final exception (plus any chained exceptions) is recorded in the context.
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::sync::LazyLock;
+
+use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::SourceTimezone;
+
+use super::{timestamp, LogParser, ParseContext};
+
+/// Regex for `asctime - name - levelname - message` lines.
+static DASHED_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(?:,\d{3})?) - (\S+) - (DEBUG|INFO|WARNING|WARN|ERROR|CRITICAL|FATAL) - (.*)$")
+        .unwrap()
+});
+
+/// Regex for `asctime levelname [name:] message` lines.
+static SPACED_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(?:,\d{3})?) \[?(DEBUG|INFO|WARNING|WARN|ERROR|CRITICAL|FATAL)\]? (?:([\w.]+): )?(.*)$")
+        .unwrap()
+});
+
+/// Regex for Celery worker lines.
+static CELERY_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^\[(\d{4}-\d{2}-\d{2} \d{2}:\d{2}:\d{2}(?:,\d{3})?): (DEBUG|INFO|WARNING|WARN|ERROR|CRITICAL|FATAL)/([\w-]+)\] (.*)$")
+        .unwrap()
+});
+
+/// Regex for the `logging.basicConfig` default format.
+static BASIC_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^(DEBUG|INFO|WARNING|WARN|ERROR|CRITICAL|FATAL):([\w.]+):(.*)$").unwrap()
+});
+
+/// Regex for the final exception line of a traceback (e.g. "ValueError: bad value").
+static EXCEPTION_LINE_REGEX: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([A-Za-z_][\w.]*)(?:: (.*))?$").unwrap());
+
+/// First line of a traceback.
+const TRACEBACK_HEADER: &str = "Traceback (most recent call last):";
+
+/// Separator between an exception and the one raised while handling it.
+const DURING_HANDLING: &str = "During handling of the above exception, another exception occurred:";
+
+/// Separator between an exception and the one it directly caused.
+const DIRECT_CAUSE: &str = "The above exception was the direct cause of the following exception:";
+
+/// Python logging parser.
+#[derive(Debug, Default, Clone)]
+pub struct PythonLogParser;
+
+impl PythonLogParser {
+    /// Create a new Python logging parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse an `asctime` timestamp, which uses a comma before the milliseconds.
+    fn parse_timestamp(
+        &self,
+        timestamp_str: &str,
+        timezone: &SourceTimezone,
+    ) -> Option<DateTime<Utc>> {
+        timestamp::parse_iso8601(&timestamp_str.replace(',', "."), timezone)
+    }
+
+    /// Extract the exceptions from traceback lines.
+    ///
+    /// Returns the exceptions in the order they appear, each with the
+    /// relation to the exception before it for chained tracebacks.
+    fn extract_exceptions(&self, lines: &[String]) -> Vec<Value> {
+        let mut exceptions = Vec::new();
+        let mut in_traceback = false;
+        let mut relation: Option<&str> = None;
+
+        for line in lines {
+            let trimmed = line.trim();
+            if trimmed == TRACEBACK_HEADER {
+                in_traceback = true;
+            } else if trimmed == DURING_HANDLING {
+                relation = Some("during_handling");
+            } else if trimmed == DIRECT_CAUSE {
+                relation = Some("direct_cause");
+            } else if in_traceback && !line.starts_with(char::is_whitespace) && !trimmed.is_empty()
+            {
+                in_traceback = false;
+                if let Some(captures) = EXCEPTION_LINE_REGEX.captures(trimmed) {
+                    let mut exception = Map::new();
+                    exception.insert("type".to_string(), json!(&captures[1]));
+                    exception.insert(
+                        "message".to_string(),
+                        json!(captures.get(2).map(|m| m.as_str()).unwrap_or("")),
+                    );
+                    if let Some(relation) = relation.take() {
+                        exception.insert("relation".to_string(), json!(relation));
+                    }
+                    exceptions.push(Value::Object(exception));
+                }
+            }
+        }
+
+        exceptions
+    }
+}
+
+impl LogParser for PythonLogParser {
+    fn name(&self) -> &'static str {
+        "Python"
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let (timestamp, level_str, logger, message) =
+            if let Some(captures) = DASHED_REGEX.captures(line) {
+                (
+                    self.parse_timestamp(captures.get(1)?.as_str(), &parse_context.timezone),
+                    captures.get(3)?.as_str(),
+                    Some(captures.get(2)?.as_str()),
+                    captures.get(4)?.as_str(),
+                )
+            } else if let Some(captures) = SPACED_REGEX.captures(line) {
+                (
+                    self.parse_timestamp(captures.get(1)?.as_str(), &parse_context.timezone),
+                    captures.get(2)?.as_str(),
+                    captures.get(3).map(|m| m.as_str()),
+                    captures.get(4)?.as_str(),
+                )
+            } else if let Some(captures) = CELERY_REGEX.captures(line) {
+                (
+                    self.parse_timestamp(captures.get(1)?.as_str(), &parse_context.timezone),
+                    captures.get(2)?.as_str(),
+                    Some(captures.get(3)?.as_str()),
+                    captures.get(4)?.as_str(),
+                )
+            } else {
+                let captures = BASIC_REGEX.captures(line)?;
+                (
+                    None,
+                    captures.get(1)?.as_str(),
+                    Some(captures.get(2)?.as_str()),
+                    captures.get(3)?.as_str(),
+                )
+            };
+
+        Some(LogEntry::new(
+            format!("python-{}", line_number),
+            timestamp,
+            parse_context.level(level_str),
+            message.to_string(),
+            line.to_string(),
+            line_number,
+            None,
+            None,
+            logger.map(str::to_string),
+        ))
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        DASHED_REGEX.is_match(line)
+            || SPACED_REGEX.is_match(line)
+            || CELERY_REGEX.is_match(line)
+            || BASIC_REGEX.is_match(line)
+    }
+
+    fn parse_multiline_with(
+        &self,
+        lines: &[&str],
+        start_line: u64,
+        parse_context: &ParseContext,
+    ) -> Option<(LogEntry, usize)> {
+        let first_line = lines.first()?;
+        let mut entry = self.parse_with(first_line, start_line, parse_context)?;
+
+        // Collect traceback lines until the next log entry starts
+        let mut stack_trace = Vec::new();
+        let mut consumed = 1;
+
+        for line in lines.iter().skip(1) {
+            if self.can_parse(line) {
+                break;
+            }
+            if !line.trim().is_empty() || !stack_trace.is_empty() {
+                stack_trace.push(line.to_string());
+                consumed += 1;
+            } else {
+                break;
+            }
+        }
+
+        if stack_trace.is_empty() {
+            return Some((entry, consumed));
+        }
+
+        let exceptions = self.extract_exceptions(&stack_trace);
+        if let Some(last) = exceptions.last().cloned() {
+            let mut context = Map::new();
+            context.insert("exception".to_string(), last);
+            if exceptions.len() > 1 {
+                context.insert("exception_chain".to_string(), Value::Array(exceptions));
+            }
+            entry = entry.with_context(Value::Object(context));
+        }
+
+        Some((entry.with_stack_trace(stack_trace), consumed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::log_watching::log_level::LogLevel;
+
+    #[test]
+    fn test_parse_dashed_format() {
+        let parser = PythonLogParser::new();
+        let line = "2024-01-15 10:30:00,123 - myapp.views - ERROR - Something failed";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.channel.as_deref(), Some("myapp.views"));
+        assert_eq!(entry.message, "Something failed");
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T10:30:00.123+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_other_formats() {
+        let parser = PythonLogParser::new();
+
+        let test_cases = [
+            (
+                "2024-01-15 10:30:00,123 WARNING django.request: Not Found: /favicon.ico",
+                LogLevel::Warning,
+                Some("django.request"),
+                "Not Found: /favicon.ico",
+            ),
+            (
+                "[2024-01-15 10:30:00,123: ERROR/ForkPoolWorker-1] Task failed",
+                LogLevel::Error,
+                Some("ForkPoolWorker-1"),
+                "Task failed",
+            ),
+            (
+                "CRITICAL:root:Out of disk space",
+                LogLevel::Critical,
+                Some("root"),
+                "Out of disk space",
+            ),
+        ];
+
+        for (line, level, channel, message) in test_cases {
+            let entry = parser.parse(line, 1).expect("Should parse log line");
+            assert_eq!(entry.level, level, "Failed for: {}", line);
+            assert_eq!(entry.channel.as_deref(), channel, "Failed for: {}", line);
+            assert_eq!(entry.message, message, "Failed for: {}", line);
+        }
+    }
+
+    #[test]
+    fn test_parse_multiline_with_traceback() {
+        let parser = PythonLogParser::new();
+        let lines = vec![
+            "2024-01-15 10:30:00,123 - myapp - ERROR - Unhandled exception",
+            "Traceback (most recent call last):",
+            "  File \"/app/views.py\", line 10, in handler",
+            "    value = int(raw)",
+            "ValueError: invalid literal for int() with base 10: 'abc'",
+            "2024-01-15 10:30:01,000 - myapp - INFO - Next entry",
+        ];
+
+        let (entry, consumed) = parser.parse_multiline(&lines, 1).expect("Should parse");
+
+        assert_eq!(consumed, 5);
+        assert_eq!(entry.stack_trace.as_ref().unwrap().len(), 4);
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["exception"]["type"], "ValueError");
+        assert_eq!(
+            context["exception"]["message"],
+            "invalid literal for int() with base 10: 'abc'"
+        );
+        assert!(context.get("exception_chain").is_none());
+    }
+
+    #[test]
+    fn test_parse_multiline_with_chained_exceptions() {
+        let parser = PythonLogParser::new();
+        let lines = vec![
+            "2024-01-15 10:30:00,123 - myapp - ERROR - Request failed",
+            "Traceback (most recent call last):",
+            "  File \"/app/db.py\", line 5, in query",
+            "KeyError: 'user'",
+            "",
+            "During handling of the above exception, another exception occurred:",
+            "",
+            "Traceback (most recent call last):",
+            "  File \"/app/views.py\", line 12, in handler",
+            "myapp.errors.NotFound: user missing",
+            "",
+            "The above exception was the direct cause of the following exception:",
+            "",
+            "Traceback (most recent call last):",
+            "  File \"/app/main.py\", line 3, in run",
+            "RuntimeError",
+        ];
+
+        let (entry, consumed) = parser.parse_multiline(&lines, 1).expect("Should parse");
+
+        assert_eq!(consumed, lines.len());
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["exception"]["type"], "RuntimeError");
+        assert_eq!(context["exception"]["message"], "");
+
+        let chain = context["exception_chain"].as_array().unwrap();
+        assert_eq!(chain.len(), 3);
+        assert_eq!(chain[0]["type"], "KeyError");
+        assert!(chain[0].get("relation").is_none());
+        assert_eq!(chain[1]["type"], "myapp.errors.NotFound");
+        assert_eq!(chain[1]["relation"], "during_handling");
+        assert_eq!(chain[2]["relation"], "direct_cause");
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let parser = PythonLogParser::new();
+
+        assert!(parser.can_parse("2024-01-15 10:30:00,123 - app - INFO - hello"));
+        assert!(parser.can_parse("INFO:root:hello"));
+        assert!(!parser.can_parse("Traceback (most recent call last):"));
+        assert!(!parser.can_parse("[2024-01-15 10:30:00] local.ERROR: Test"));
+    }
+}
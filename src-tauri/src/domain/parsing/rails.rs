@@ -0,0 +1,202 @@
+//! Rails logger parser.
+//!
+//! Parses the Ruby `Logger` default format used by Rails:
+//! E, [2024-01-15T10:30:00.123456 #1234] ERROR -- progname: Message
+//!
+//! The process ID is recorded in the context and the program name, when
+//! set, as the channel. Lines following an entry that do not start a new
+//! one (backtraces, multi-line messages) are attached as its stack trace.
+
+use chrono::{DateTime, Utc};
+use regex::Regex;
+use serde_json::json;
+use std::sync::LazyLock;
+
+use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::SourceTimezone;
+
+use super::{timestamp, LogParser, ParseContext};
+
+/// Regex for Ruby Logger lines: severity letter, timestamp, pid, level, progname and message.
+static RAILS_LOG_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^([DIWEFAU]), \[(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?)\s*#(\d+)\]\s+(DEBUG|INFO|WARN|ERROR|FATAL|UNKNOWN|ANY) -- ([^:]*): ?(.*)$",
+    )
+    .unwrap()
+});
+
+/// Rails logger parser.
+#[derive(Debug, Default, Clone)]
+pub struct RailsLogParser;
+
+impl RailsLogParser {
+    /// Create a new Rails logger parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Parse a Logger timestamp, which has no offset.
+    fn parse_timestamp(
+        &self,
+        timestamp_str: &str,
+        timezone: &SourceTimezone,
+    ) -> Option<DateTime<Utc>> {
+        timestamp::parse_iso8601(timestamp_str, timezone)
+    }
+}
+
+impl LogParser for RailsLogParser {
+    fn name(&self) -> &'static str {
+        "Rails"
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let captures = RAILS_LOG_REGEX.captures(line)?;
+
+        let timestamp_str = captures.get(2)?.as_str();
+        let pid: u64 = captures.get(3)?.as_str().parse().ok()?;
+        let level_str = captures.get(4)?.as_str();
+        let progname = captures.get(5)?.as_str().trim();
+        let message = captures.get(6)?.as_str();
+
+        Some(LogEntry::new(
+            format!("rails-{}", line_number),
+            self.parse_timestamp(timestamp_str, &parse_context.timezone),
+            parse_context.level(level_str),
+            message.trim_end().to_string(),
+            line.to_string(),
+            line_number,
+            Some(json!({ "pid": pid })),
+            None,
+            Some(progname.to_string()).filter(|p| !p.is_empty()),
+        ))
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        RAILS_LOG_REGEX.is_match(line)
+    }
+
+    fn parse_multiline_with(
+        &self,
+        lines: &[&str],
+        start_line: u64,
+        parse_context: &ParseContext,
+    ) -> Option<(LogEntry, usize)> {
+        let first_line = lines.first()?;
+        let entry = self.parse_with(first_line, start_line, parse_context)?;
+
+        // Collect continuation lines until the next log entry starts
+        let mut stack_trace = Vec::new();
+        let mut consumed = 1;
+
+        for line in lines.iter().skip(1) {
+            if self.can_parse(line) {
+                break;
+            }
+            if !line.trim().is_empty() || !stack_trace.is_empty() {
+                stack_trace.push(line.to_string());
+                consumed += 1;
+            } else {
+                break;
+            }
+        }
+
+        if stack_trace.is_empty() {
+            return Some((entry, consumed));
+        }
+
+        Some((entry.with_stack_trace(stack_trace), consumed))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::log_watching::log_level::LogLevel;
+
+    #[test]
+    fn test_parse_basic_log_line() {
+        let parser = RailsLogParser::new();
+        let line = "E, [2024-01-15T10:30:00.123456 #1234] ERROR -- : Something failed";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        assert_eq!(entry.message, "Something failed");
+        assert_eq!(entry.context.unwrap()["pid"], 1234);
+        assert!(entry.channel.is_none());
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T10:30:00.123456+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_levels_and_progname() {
+        let parser = RailsLogParser::new();
+
+        let test_cases = [
+            (
+                "I, [2024-01-15T10:30:00.123 #1]  INFO -- : Started GET \"/\"",
+                LogLevel::Info,
+                None,
+            ),
+            (
+                "W, [2024-01-15T10:30:00.123#1]  WARN -- Sidekiq: Retrying job",
+                LogLevel::Warning,
+                Some("Sidekiq"),
+            ),
+            (
+                "D, [2024-01-15T10:30:00.123 #1] DEBUG -- :   User Load (0.4ms)",
+                LogLevel::Debug,
+                None,
+            ),
+            (
+                "F, [2024-01-15T10:30:00.123 #1] FATAL -- : [req-1] ActionController::RoutingError",
+                LogLevel::Critical,
+                None,
+            ),
+        ];
+
+        for (line, expected_level, expected_channel) in test_cases {
+            let entry = parser.parse(line, 1).expect("Should parse log line");
+            assert_eq!(entry.level, expected_level, "Failed for: {}", line);
+            assert_eq!(
+                entry.channel.as_deref(),
+                expected_channel,
+                "Failed for: {}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_multiline_with_backtrace() {
+        let parser = RailsLogParser::new();
+        let lines = vec![
+            "E, [2024-01-15T10:30:00.123456 #1234] ERROR -- : NoMethodError (undefined method `name' for nil):",
+            "app/controllers/users_controller.rb:10:in `show'",
+            "actionpack (7.1.2) lib/action_controller/metal/basic_implicit_render.rb:6:in `send_action'",
+            "I, [2024-01-15T10:30:01.000000 #1234]  INFO -- : Completed 500",
+        ];
+
+        let (entry, consumed) = parser.parse_multiline(&lines, 1).expect("Should parse");
+
+        assert_eq!(consumed, 3);
+        assert_eq!(entry.stack_trace.unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let parser = RailsLogParser::new();
+
+        assert!(parser.can_parse("I, [2024-01-15T10:30:00.123456 #1]  INFO -- : Hello"));
+        assert!(!parser.can_parse("[2024-01-15 10:30:00] local.INFO: Laravel"));
+        assert!(!parser.can_parse("Plain text log"));
+    }
+}
@@ -0,0 +1,138 @@
+//! Symfony log parser.
+//!
+//! Parses the Monolog line format used by Symfony:
+//! [2024-01-15T10:30:00.123456+00:00] channel.LEVEL: Message {"context": ...} {"extra": ...}
+//!
+//! The layout matches Laravel's apart from the ISO 8601 timestamp with
+//! microseconds and offset, so context, extra and serialised exceptions are
+//! handled by the shared [`MonologLineFormat`].
+
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::domain::log_watching::log_entry::LogEntry;
+
+use super::laravel::MonologLineFormat;
+use super::{LogParser, ParseContext};
+
+/// Regex for parsing Symfony log lines.
+static SYMFONY_LOG_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^\[(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}(?:\.\d+)?(?:Z|[+-]\d{2}:?\d{2})?)\]\s+([\w-]+)\.(\w+):\s*(.*)$",
+    )
+    .unwrap()
+});
+
+/// Symfony log parser.
+#[derive(Debug, Default, Clone)]
+pub struct SymfonyLogParser;
+
+impl SymfonyLogParser {
+    /// Create a new Symfony log parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// The Symfony line layout.
+    fn format(&self) -> MonologLineFormat {
+        MonologLineFormat::new(&SYMFONY_LOG_REGEX, "symfony")
+    }
+}
+
+impl LogParser for SymfonyLogParser {
+    fn name(&self) -> &'static str {
+        "Symfony"
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        self.format().parse(line, line_number, parse_context)
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        self.format().can_parse(line)
+    }
+
+    fn parse_multiline_with(
+        &self,
+        lines: &[&str],
+        start_line: u64,
+        parse_context: &ParseContext,
+    ) -> Option<(LogEntry, usize)> {
+        self.format()
+            .parse_multiline(lines, start_line, parse_context)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::log_watching::log_level::LogLevel;
+
+    #[test]
+    fn test_parse_basic_log_line() {
+        let parser = SymfonyLogParser::new();
+        let line = r#"[2024-01-15T10:30:00.123456+01:00] request.INFO: Matched route "home". {"route":"home"} []"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Info);
+        assert_eq!(entry.channel.as_deref(), Some("request"));
+        assert_eq!(entry.message, r#"Matched route "home"."#);
+        assert_eq!(entry.context.unwrap()["route"], "home");
+        assert!(entry.extra.is_none());
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T09:30:00.123456+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_exception_context() {
+        let parser = SymfonyLogParser::new();
+        let line = r#"[2024-01-15T10:30:00.000000+00:00] request.ERROR: Uncaught PHP Exception Symfony\Component\HttpKernel\Exception\NotFoundHttpException: "No route found" at RouterListener.php line 135 {"exception":"[object] (Symfony\\Component\\HttpKernel\\Exception\\NotFoundHttpException(code: 0): No route found at /app/vendor/symfony/http-kernel/EventListener/RouterListener.php:135)"} []"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Error);
+        let exception = entry.exception.expect("Should have exception");
+        assert_eq!(
+            exception.class,
+            "Symfony\\Component\\HttpKernel\\Exception\\NotFoundHttpException"
+        );
+        assert_eq!(exception.message, "No route found");
+        assert_eq!(exception.line, Some(135));
+    }
+
+    #[test]
+    fn test_parse_multiline_with_stack_trace() {
+        let parser = SymfonyLogParser::new();
+        let lines = vec![
+            "[2024-01-15T10:30:00.000000+00:00] app.CRITICAL: Payment failed",
+            "#0 /app/src/Controller/PaymentController.php(42): App\\Service\\Gateway->charge()",
+            "#1 {main}",
+            "[2024-01-15T10:30:01.000000+00:00] app.INFO: Next entry",
+        ];
+
+        let (entry, consumed) = parser.parse_multiline(&lines, 1).expect("Should parse");
+
+        assert_eq!(consumed, 3);
+        assert_eq!(entry.level, LogLevel::Critical);
+        assert_eq!(entry.stack_trace.unwrap().len(), 2);
+        assert_eq!(entry.stack_frames.len(), 2);
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let parser = SymfonyLogParser::new();
+
+        assert!(parser.can_parse("[2024-01-15T10:30:00.123456+00:00] doctrine.DEBUG: SELECT 1"));
+        assert!(parser.can_parse("[2024-01-15T10:30:00+00:00] php.WARNING: Deprecated"));
+        assert!(!parser.can_parse("[2024-01-15 10:30:00] local.ERROR: Laravel format"));
+        assert!(!parser.can_parse("Plain text log"));
+    }
+}
@@ -0,0 +1,420 @@
+//! Syslog parser.
+//!
+//! Parses both syslog formats found under /var/log:
+//! - RFC 3164 (BSD): `<34>Jan 15 10:30:00 web-1 sshd[1234]: Accepted publickey for deploy`
+//! - RFC 5424: `<165>1 2024-01-15T10:30:00.003Z web-1 app 1234 ID47 [meta@1 env="prod"] Message`
+//!
+//! The PRI prefix is optional for RFC 3164, since most files on disk omit it.
+//! Without it, a `tag[pid]:` or `tag:` segment is required, so that other
+//! timestamped lines are not taken for syslog.
+//! rsyslog's high-precision traditional format (RFC 3339 timestamp instead of
+//! `Jan 15 10:30:00`) is also supported.
+
+use chrono::Utc;
+use regex::{Captures, Regex};
+use serde_json::{json, Map, Value};
+use std::sync::LazyLock;
+
+use crate::domain::log_watching::log_entry::LogEntry;
+use crate::domain::log_watching::log_level::LogLevel;
+
+use super::{timestamp, LogParser, ParseContext};
+
+/// Regex for parsing RFC 5424 syslog lines up to the structured data.
+static RFC5424_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^<(\d{1,3})>(\d{1,2}) (\S+) (\S+) (\S+) (\S+) (\S+) (.*)$").unwrap()
+});
+
+/// Regex for parsing RFC 3164 (BSD) syslog lines.
+static RFC3164_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^(?:<(\d{1,3})>)?(\w{3} +\d{1,2} \d{2}:\d{2}:\d{2}|\d{4}-\d{2}-\d{2}T\S+) (\S+) (?:([^\s\[\]:]+)(?:\[(\d+)\])?: ?)?(.*)$",
+    )
+    .unwrap()
+});
+
+/// Syslog parser for RFC 3164 and RFC 5424 formats.
+#[derive(Debug, Default, Clone)]
+pub struct SyslogParser;
+
+impl SyslogParser {
+    /// Create a new syslog parser.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Match an RFC 3164 line, requiring a tag when the PRI is missing.
+    fn rfc3164_captures(line: &str) -> Option<Captures<'_>> {
+        RFC3164_REGEX
+            .captures(line)
+            .filter(|captures| captures.get(1).is_some() || captures.get(4).is_some())
+    }
+
+    /// Split a PRI value into facility and severity level.
+    ///
+    /// The numeric severity is looked up in the source's aliases first.
+    fn parse_pri(&self, pri: &str, parse_context: &ParseContext) -> Option<(u8, LogLevel)> {
+        let pri: u8 = pri.parse().ok().filter(|p| *p <= 191)?;
+        let severity = pri % 8;
+        if let Some(level) = parse_context.level_alias(&severity.to_string()) {
+            return Some((pri / 8, level));
+        }
+        let level = match severity {
+            0 => LogLevel::Emergency,
+            1 => LogLevel::Alert,
+            2 => LogLevel::Critical,
+            3 => LogLevel::Error,
+            4 => LogLevel::Warning,
+            5 => LogLevel::Notice,
+            6 => LogLevel::Info,
+            _ => LogLevel::Debug,
+        };
+        Some((pri / 8, level))
+    }
+
+    /// Parse RFC 5424 structured data from the start of `input`.
+    ///
+    /// Returns the structured data elements (if any) and the remaining message.
+    fn parse_structured_data<'a>(&self, input: &'a str) -> Option<(Map<String, Value>, &'a str)> {
+        let mut elements = Map::new();
+
+        if let Some(rest) = input.strip_prefix('-') {
+            return Some((elements, rest));
+        }
+
+        let mut chars = input.char_indices().peekable();
+        while let Some((_, '[')) = chars.peek().copied() {
+            chars.next();
+
+            let mut id = String::new();
+            while let Some((_, c)) = chars.next_if(|(_, c)| *c != ' ' && *c != ']') {
+                id.push(c);
+            }
+
+            let mut params = Map::new();
+            loop {
+                match chars.next()? {
+                    (_, ']') => break,
+                    (_, ' ') => {
+                        let mut name = String::new();
+                        while let Some((_, c)) = chars.next_if(|(_, c)| *c != '=') {
+                            name.push(c);
+                        }
+                        chars.next_if(|(_, c)| *c == '=')?;
+                        chars.next_if(|(_, c)| *c == '"')?;
+
+                        let mut value = String::new();
+                        loop {
+                            match chars.next()? {
+                                (_, '"') => break,
+                                (_, '\\') => {
+                                    let (_, escaped) = chars.next()?;
+                                    if !matches!(escaped, '"' | '\\' | ']') {
+                                        value.push('\\');
+                                    }
+                                    value.push(escaped);
+                                }
+                                (_, c) => value.push(c),
+                            }
+                        }
+                        params.insert(name, json!(value));
+                    }
+                    _ => return None,
+                }
+            }
+            elements.insert(id, Value::Object(params));
+        }
+
+        let rest = chars.peek().map(|(i, _)| &input[*i..]).unwrap_or("");
+        Some((elements, rest))
+    }
+
+    /// Convert a "-" nil value to None.
+    fn non_nil(value: &str) -> Option<&str> {
+        (value != "-").then_some(value)
+    }
+
+    /// Parse an RFC 5424 line.
+    fn parse_rfc5424(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let captures = RFC5424_REGEX.captures(line)?;
+
+        let (facility, level) = self.parse_pri(captures.get(1)?.as_str(), parse_context)?;
+        let timestamp = Self::non_nil(captures.get(3)?.as_str())
+            .and_then(|s| timestamp::parse_iso8601(s, &parse_context.timezone));
+        let hostname = Self::non_nil(captures.get(4)?.as_str());
+        let app_name = Self::non_nil(captures.get(5)?.as_str());
+        let proc_id = Self::non_nil(captures.get(6)?.as_str());
+        let msg_id = Self::non_nil(captures.get(7)?.as_str());
+        let (structured_data, rest) = self.parse_structured_data(captures.get(8)?.as_str())?;
+
+        let message = rest.strip_prefix(' ').unwrap_or(rest);
+        let message = message.strip_prefix('\u{feff}').unwrap_or(message);
+
+        let mut context = Map::new();
+        context.insert("facility".to_string(), json!(facility));
+        if let Some(hostname) = hostname {
+            context.insert("hostname".to_string(), json!(hostname));
+        }
+        if let Some(app_name) = app_name {
+            context.insert("app_name".to_string(), json!(app_name));
+        }
+        if let Some(proc_id) = proc_id {
+            match proc_id.parse::<u32>() {
+                Ok(pid) => context.insert("pid".to_string(), json!(pid)),
+                Err(_) => context.insert("proc_id".to_string(), json!(proc_id)),
+            };
+        }
+        if let Some(msg_id) = msg_id {
+            context.insert("msg_id".to_string(), json!(msg_id));
+        }
+        if !structured_data.is_empty() {
+            context.insert(
+                "structured_data".to_string(),
+                Value::Object(structured_data),
+            );
+        }
+
+        Some(LogEntry::new(
+            format!("syslog-{}", line_number),
+            timestamp,
+            level,
+            message.to_string(),
+            line.to_string(),
+            line_number,
+            Some(Value::Object(context)),
+            None,
+            app_name.map(str::to_string),
+        ))
+    }
+
+    /// Parse an RFC 3164 line.
+    fn parse_rfc3164(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        let captures = Self::rfc3164_captures(line)?;
+
+        let pri = match captures.get(1) {
+            Some(pri) => Some(self.parse_pri(pri.as_str(), parse_context)?),
+            None => None,
+        };
+        let timestamp_str = captures.get(2)?.as_str();
+        let hostname = captures.get(3)?.as_str();
+        let app_name = captures.get(4).map(|m| m.as_str());
+        let pid = captures.get(5).and_then(|m| m.as_str().parse::<u32>().ok());
+        let message = captures.get(6)?.as_str();
+
+        let timestamp = if timestamp_str.as_bytes()[0].is_ascii_digit() {
+            timestamp::parse_iso8601(timestamp_str, &parse_context.timezone)
+        } else {
+            timestamp::parse_bsd(timestamp_str, Utc::now(), &parse_context.timezone)
+        };
+
+        let mut context = Map::new();
+        if let Some((facility, _)) = pri {
+            context.insert("facility".to_string(), json!(facility));
+        }
+        context.insert("hostname".to_string(), json!(hostname));
+        if let Some(app_name) = app_name {
+            context.insert("app_name".to_string(), json!(app_name));
+        }
+        if let Some(pid) = pid {
+            context.insert("pid".to_string(), json!(pid));
+        }
+
+        Some(LogEntry::new(
+            format!("syslog-{}", line_number),
+            timestamp,
+            pri.map(|(_, level)| level).unwrap_or_default(),
+            message.to_string(),
+            line.to_string(),
+            line_number,
+            Some(Value::Object(context)),
+            None,
+            app_name.map(str::to_string),
+        ))
+    }
+}
+
+impl LogParser for SyslogParser {
+    fn name(&self) -> &'static str {
+        "Syslog"
+    }
+
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry> {
+        self.parse_rfc5424(line, line_number, parse_context)
+            .or_else(|| self.parse_rfc3164(line, line_number, parse_context))
+    }
+
+    fn can_parse(&self, line: &str) -> bool {
+        RFC5424_REGEX.is_match(line) || Self::rfc3164_captures(line).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rfc3164_without_pri() {
+        let parser = SyslogParser::new();
+        let line = "Jan 15 10:30:00 web-1 sshd[1234]: Accepted publickey for deploy from 10.0.0.1";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Info);
+        assert_eq!(entry.channel.as_deref(), Some("sshd"));
+        assert_eq!(entry.message, "Accepted publickey for deploy from 10.0.0.1");
+        assert!(entry.timestamp.is_some());
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["hostname"], "web-1");
+        assert_eq!(context["app_name"], "sshd");
+        assert_eq!(context["pid"], 1234);
+    }
+
+    #[test]
+    fn test_parse_rfc3164_with_pri() {
+        let parser = SyslogParser::new();
+        let line = "<34>Oct 11 22:14:15 mymachine su: 'su root' failed for lonvick on /dev/pts/8";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Critical);
+        assert_eq!(entry.channel.as_deref(), Some("su"));
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["facility"], 4);
+        assert!(context.get("pid").is_none());
+    }
+
+    #[test]
+    fn test_parse_rfc3164_kernel_line() {
+        let parser = SyslogParser::new();
+        let line = "Jan  5 08:00:01 web-1 kernel: [12345.678901] Out of memory: Killed process 42";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.channel.as_deref(), Some("kernel"));
+        assert_eq!(
+            entry.message,
+            "[12345.678901] Out of memory: Killed process 42"
+        );
+    }
+
+    #[test]
+    fn test_parse_high_precision_traditional_format() {
+        let parser = SyslogParser::new();
+        let line = "2024-01-15T10:30:00.123456+01:00 web-1 systemd[1]: Started Session 5.";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.channel.as_deref(), Some("systemd"));
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2024-01-15T09:30:00.123456+00:00"
+        );
+    }
+
+    #[test]
+    fn test_parse_rfc5424() {
+        let parser = SyslogParser::new();
+        let line = r#"<165>1 2003-10-11T22:14:15.003Z mymachine.example.com evntslog - ID47 [exampleSDID@32473 iut="3" eventSource="Application" eventID="1011"][meta note="a \"quoted\" \] value"] An application event"#;
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Notice);
+        assert_eq!(entry.channel.as_deref(), Some("evntslog"));
+        assert_eq!(entry.message, "An application event");
+        assert_eq!(
+            entry.timestamp.unwrap().to_rfc3339(),
+            "2003-10-11T22:14:15.003+00:00"
+        );
+
+        let context = entry.context.expect("Should have context");
+        assert_eq!(context["facility"], 20);
+        assert_eq!(context["hostname"], "mymachine.example.com");
+        assert_eq!(context["msg_id"], "ID47");
+        assert!(context.get("pid").is_none());
+        assert_eq!(
+            context["structured_data"]["exampleSDID@32473"]["eventSource"],
+            "Application"
+        );
+        assert_eq!(
+            context["structured_data"]["meta"]["note"],
+            r#"a "quoted" ] value"#
+        );
+    }
+
+    #[test]
+    fn test_parse_rfc5424_nil_values() {
+        let parser = SyslogParser::new();
+        let line = "<13>1 - - - 42 - - \u{feff}Message with BOM";
+
+        let entry = parser.parse(line, 1).expect("Should parse log line");
+
+        assert_eq!(entry.level, LogLevel::Notice);
+        assert!(entry.timestamp.is_none());
+        assert!(entry.channel.is_none());
+        assert_eq!(entry.message, "Message with BOM");
+        assert_eq!(entry.context.expect("Should have context")["pid"], 42);
+    }
+
+    #[test]
+    fn test_level_aliases_for_severity() {
+        let parser = SyslogParser::new();
+        let parse_context = ParseContext {
+            level_aliases: [("6", LogLevel::Debug)].into_iter().collect(),
+            ..ParseContext::utc()
+        };
+
+        let entry = parser
+            .parse_with("<14>Jan 15 10:30:00 web-1 app: Polling", 1, &parse_context)
+            .expect("Should parse log line");
+        assert_eq!(entry.level, LogLevel::Debug);
+        assert_eq!(entry.context.expect("Should have context")["facility"], 1);
+
+        let entry = parser
+            .parse_with("<11>1 - - app - - - Unaliased", 2, &parse_context)
+            .expect("Should parse log line");
+        assert_eq!(entry.level, LogLevel::Error);
+    }
+
+    #[test]
+    fn test_reject_untagged_line_without_pri() {
+        let parser = SyslogParser::new();
+
+        assert!(parser
+            .parse("2024-01-15T10:30:00Z ERROR Database down", 1)
+            .is_none());
+        assert!(parser
+            .parse("Jan 15 10:30:00 web-1 no tag here", 1)
+            .is_none());
+        assert!(parser
+            .parse("<14>Jan 15 10:30:00 web-1 no tag here", 1)
+            .is_some());
+    }
+
+    #[test]
+    fn test_can_parse() {
+        let parser = SyslogParser::new();
+
+        assert!(parser.can_parse("Jan 15 10:30:00 host cron[1]: job done"));
+        assert!(parser.can_parse("<14>1 2024-01-15T10:30:00Z host app - - - msg"));
+        assert!(!parser.can_parse("[2024-01-15 10:30:00] local.ERROR: Test"));
+        assert!(!parser.can_parse("Plain text log"));
+        assert!(!parser.can_parse("2024-01-15T10:30:00Z ERROR Database down"));
+    }
+}
@@ -0,0 +1,248 @@
+//! Timestamp helpers shared by the log parsers.
+
+use chrono::{DateTime, Datelike, Duration, NaiveDateTime, Utc};
+use regex::Regex;
+use std::sync::LazyLock;
+
+use crate::domain::log_watching::SourceTimezone;
+
+/// Naive date-time formats tried after RFC 3339, in order.
+const NAIVE_FORMATS: &[&str] = &["%Y-%m-%dT%H:%M:%S%.f", "%Y-%m-%d %H:%M:%S%.f"];
+
+/// Regex for a timestamp at the start of a line, optionally in brackets.
+static LEADING_TIMESTAMP_REGEX: LazyLock<Regex> = LazyLock::new(|| {
+    const MONTH: &str = "(?:Jan|Feb|Mar|Apr|May|Jun|Jul|Aug|Sep|Oct|Nov|Dec)";
+    Regex::new(&format!(
+        concat!(
+            r"^\[?(?:",
+            r"(?P<iso>\d{{4}}-\d{{2}}-\d{{2}})[T ](?P<iso_time>\d{{2}}:\d{{2}}:\d{{2}}(?:[.,]\d+)?)",
+            r"(?:\s?(?P<iso_offset>Z|[+-]\d{{2}}:?\d{{2}}))?",
+            r"|(?P<rfc2822>(?:(?:Mon|Tue|Wed|Thu|Fri|Sat|Sun),\s+)?\d{{1,2}}\s+{m}\s+\d{{4}}\s+\d{{2}}:\d{{2}}(?::\d{{2}})?\s+(?:[+-]\d{{4}}|GMT|UTC|UT|Z))",
+            r"|(?P<clf>\d{{2}}/{m}/\d{{4}}:\d{{2}}:\d{{2}}:\d{{2}}\s[+-]\d{{4}})",
+            r"|(?P<bsd>{m}\s+\d{{1,2}}\s\d{{2}}:\d{{2}}:\d{{2}})",
+            r"|(?P<epoch>\d{{10}}(?:\d{{3}}){{0,3}}(?:\.\d+)?)",
+            r")\b\]?",
+        ),
+        m = MONTH
+    ))
+    .unwrap()
+});
+
+/// Epoch timestamps outside this range of years are taken to be plain numbers.
+const EPOCH_YEARS: std::ops::RangeInclusive<i32> = 2000..=2100;
+
+/// Parse an ISO-8601 style timestamp, with or without an offset.
+///
+/// Timestamps without an offset are interpreted in the given timezone.
+pub(crate) fn parse_iso8601(s: &str, timezone: &SourceTimezone) -> Option<DateTime<Utc>> {
+    let s = s.trim();
+    if let Ok(dt) = DateTime::parse_from_rfc3339(s) {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = DateTime::parse_from_str(s, "%Y-%m-%d %H:%M:%S%.f%:z") {
+        return Some(dt.with_timezone(&Utc));
+    }
+    if let Ok(dt) = DateTime::parse_from_str(s, "%Y-%m-%dT%H:%M:%S%.f%z") {
+        return Some(dt.with_timezone(&Utc));
+    }
+    NAIVE_FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+        .map(|dt| timezone.to_utc(dt))
+}
+
+/// Parse a BSD syslog timestamp such as `Jan 15 10:30:00`, which has no year.
+///
+/// The year is inferred relative to `now`: the current year is used
+/// unless that would put the entry in the future, in which case the
+/// entry is assumed to be from the previous year.
+pub(crate) fn parse_bsd(
+    s: &str,
+    now: DateTime<Utc>,
+    timezone: &SourceTimezone,
+) -> Option<DateTime<Utc>> {
+    let normalized = s.split_whitespace().collect::<Vec<_>>().join(" ");
+    let tolerance = Duration::days(1);
+
+    [now.year(), now.year() - 1].into_iter().find_map(|year| {
+        NaiveDateTime::parse_from_str(&format!("{} {}", year, normalized), "%Y %b %d %H:%M:%S")
+            .ok()
+            .map(|dt| timezone.to_utc(dt))
+            .filter(|dt| *dt <= now + tolerance)
+    })
+}
+
+/// Convert an epoch value to a timestamp, guessing the unit from its magnitude.
+///
+/// Values are treated as seconds, milliseconds, microseconds or nanoseconds
+/// depending on how many digits they have.
+pub(crate) fn from_epoch(epoch: f64) -> Option<DateTime<Utc>> {
+    let magnitude = epoch.abs();
+    let nanos = if magnitude < 1e11 {
+        epoch * 1e9
+    } else if magnitude < 1e14 {
+        epoch * 1e6
+    } else if magnitude < 1e17 {
+        epoch * 1e3
+    } else {
+        epoch
+    };
+    if !nanos.is_finite() || nanos.abs() >= i64::MAX as f64 {
+        return None;
+    }
+    Some(DateTime::from_timestamp_nanos(nanos as i64))
+}
+
+/// Parse a timestamp string that is either ISO-8601 or a numeric epoch.
+pub(crate) fn parse_iso8601_or_epoch(s: &str, timezone: &SourceTimezone) -> Option<DateTime<Utc>> {
+    match s.trim().parse::<f64>() {
+        Ok(epoch) => from_epoch(epoch),
+        Err(_) => parse_iso8601(s, timezone),
+    }
+}
+
+/// Detect a timestamp at the start of a line in any of the common formats.
+///
+/// Recognises ISO-8601, RFC 2822 (and HTTP dates), access log
+/// (`15/Jan/2024:10:30:00 +0000`), BSD syslog (`Jan 15 10:30:00`) and
+/// epoch seconds or milliseconds, optionally wrapped in brackets. Returns
+/// the timestamp and the length of the matched prefix.
+pub(crate) fn detect_leading(
+    line: &str,
+    timezone: &SourceTimezone,
+) -> Option<(DateTime<Utc>, usize)> {
+    let captures = LEADING_TIMESTAMP_REGEX.captures(line)?;
+
+    let timestamp = if let Some(date) = captures.name("iso") {
+        let offset = captures.name("iso_offset").map_or("", |m| m.as_str());
+        let time = captures.name("iso_time")?.as_str().replace(',', ".");
+        parse_iso8601(&format!("{}T{}{}", date.as_str(), time, offset), timezone)
+    } else if let Some(m) = captures.name("rfc2822") {
+        DateTime::parse_from_rfc2822(m.as_str())
+            .ok()
+            .map(|dt| dt.with_timezone(&Utc))
+    } else if let Some(m) = captures.name("clf") {
+        DateTime::parse_from_str(m.as_str(), "%d/%b/%Y:%H:%M:%S %z")
+            .ok()
+            .map(|dt| dt.with_timezone(&Utc))
+    } else if let Some(m) = captures.name("bsd") {
+        parse_bsd(m.as_str(), Utc::now(), timezone)
+    } else {
+        let epoch = captures.name("epoch")?.as_str().parse::<f64>().ok()?;
+        from_epoch(epoch).filter(|dt| EPOCH_YEARS.contains(&dt.year()))
+    }?;
+
+    Some((timestamp, captures.get(0)?.end()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_iso8601() {
+        let expected = "2024-01-15T10:30:00+00:00";
+        let cases = [
+            "2024-01-15T10:30:00Z",
+            "2024-01-15T11:30:00+01:00",
+            "2024-01-15 11:30:00+01:00",
+            "2024-01-15T11:30:00+0100",
+            "2024-01-15T10:30:00",
+            "2024-01-15 10:30:00",
+        ];
+
+        for case in cases {
+            let ts = parse_iso8601(case, &SourceTimezone::UTC)
+                .unwrap_or_else(|| panic!("Failed for: {}", case));
+            assert_eq!(ts.to_rfc3339(), expected, "Failed for: {}", case);
+        }
+    }
+
+    #[test]
+    fn test_parse_iso8601_naive_uses_timezone() {
+        let oslo: SourceTimezone = "Europe/Oslo".parse().unwrap();
+
+        let naive = parse_iso8601("2024-01-15 11:30:00", &oslo).unwrap();
+        let explicit = parse_iso8601("2024-01-15T11:30:00-05:00", &oslo).unwrap();
+
+        assert_eq!(naive.to_rfc3339(), "2024-01-15T10:30:00+00:00");
+        assert_eq!(explicit.to_rfc3339(), "2024-01-15T16:30:00+00:00");
+    }
+
+    #[test]
+    fn test_from_epoch_units() {
+        let expected = "2024-01-15T10:30:00+00:00";
+        for epoch in [
+            1_705_314_600.0,
+            1_705_314_600_000.0,
+            1_705_314_600_000_000.0,
+            1_705_314_600_000_000_000.0,
+        ] {
+            assert_eq!(from_epoch(epoch).unwrap().to_rfc3339(), expected);
+        }
+    }
+
+    #[test]
+    fn test_parse_bsd_infers_year() {
+        use chrono::TimeZone;
+        let now = Utc.with_ymd_and_hms(2024, 1, 2, 12, 0, 0).unwrap();
+
+        let this_year = parse_bsd("Jan  1 10:00:00", now, &SourceTimezone::UTC).unwrap();
+        assert_eq!(this_year.year(), 2024);
+
+        let last_year = parse_bsd("Dec 31 23:59:59", now, &SourceTimezone::UTC).unwrap();
+        assert_eq!(last_year.year(), 2023);
+    }
+
+    #[test]
+    fn test_detect_leading() {
+        let expected = "2024-01-15T10:30:00+00:00";
+        let cases = [
+            ("2024-01-15T10:30:00Z something happened", 20),
+            ("2024-01-15 10:30:00,000 something happened", 23),
+            ("[2024-01-15 11:30:00 +0100] something happened", 27),
+            ("Mon, 15 Jan 2024 10:30:00 GMT something happened", 29),
+            ("15 Jan 2024 11:30:00 +0100 something happened", 26),
+            ("15/Jan/2024:10:30:00 +0000 something happened", 26),
+            ("1705314600000 something happened", 13),
+            ("1705314600.000 something happened", 14),
+        ];
+
+        for (line, len) in cases {
+            let (ts, end) = detect_leading(line, &SourceTimezone::UTC)
+                .unwrap_or_else(|| panic!("Failed for: {}", line));
+            assert_eq!(ts.to_rfc3339(), expected, "Failed for: {}", line);
+            assert_eq!(end, len, "Failed for: {}", line);
+        }
+
+        let (_, end) = detect_leading("Jan 15 10:30:00 host app", &SourceTimezone::UTC).unwrap();
+        assert_eq!(end, 15);
+    }
+
+    #[test]
+    fn test_detect_leading_rejects_non_timestamps() {
+        let cases = [
+            "Server started on port 8080",
+            "12345 items processed",
+            "9999999999999 bytes",
+            "2024-01-15T10:30:00abc",
+            "Janet 15 10:30:00",
+        ];
+
+        for line in cases {
+            assert!(
+                detect_leading(line, &SourceTimezone::UTC).is_none(),
+                "Failed for: {}",
+                line
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_iso8601_or_epoch() {
+        let utc = SourceTimezone::UTC;
+        assert!(parse_iso8601_or_epoch("1705314600", &utc).is_some());
+        assert!(parse_iso8601_or_epoch("2024-01-15T10:30:00Z", &utc).is_some());
+        assert!(parse_iso8601_or_epoch("yesterday", &utc).is_none());
+    }
+}
@@ -2,24 +2,53 @@
 
 use crate::domain::log_watching::LogEntry;
 
+use super::ParseContext;
+
 /// Trait for log parsers.
 pub trait LogParser: Send + Sync {
     /// Returns the name of this parser.
-    fn name(&self) -> &'static str;
+    fn name(&self) -> &str;
 
-    /// Attempts to parse a log line.
+    /// Attempts to parse a log line, reading timestamps without an offset as UTC.
     ///
     /// Returns Some(LogEntry) if the line matches this parser's format,
     /// or None if it doesn't match.
-    fn parse(&self, line: &str, line_number: u64) -> Option<LogEntry>;
+    fn parse(&self, line: &str, line_number: u64) -> Option<LogEntry> {
+        self.parse_with(line, line_number, &ParseContext::utc())
+    }
+
+    /// Attempts to parse a log line using the settings of its source.
+    fn parse_with(
+        &self,
+        line: &str,
+        line_number: u64,
+        parse_context: &ParseContext,
+    ) -> Option<LogEntry>;
 
     /// Checks if this parser can handle the given line.
     fn can_parse(&self, line: &str) -> bool;
 
-    /// Attempts to parse a multi-line log entry (e.g., stack traces).
+    /// Attempts to parse a multi-line log entry (e.g., stack traces),
+    /// reading timestamps without an offset as UTC.
     fn parse_multiline(&self, lines: &[&str], start_line: u64) -> Option<(LogEntry, usize)> {
+        self.parse_multiline_with(lines, start_line, &ParseContext::utc())
+    }
+
+    /// Attempts to parse a multi-line log entry using the settings of its source.
+    fn parse_multiline_with(
+        &self,
+        lines: &[&str],
+        start_line: u64,
+        parse_context: &ParseContext,
+    ) -> Option<(LogEntry, usize)> {
         // Default implementation: just parse the first line
-        self.parse(lines.first()?, start_line)
+        self.parse_with(lines.first()?, start_line, parse_context)
             .map(|entry| (entry, 1))
     }
+
+    /// Checks if a line is only the start of an entry that the next line
+    /// completes, such as a CRI `P` line.
+    fn continues_on_next_line(&self, _line: &str) -> bool {
+        false
+    }
 }
@@ -1,12 +1,32 @@
 //! File watcher implementation using the notify crate.
+//!
+//! Files are followed by name, like `tail -F`: each file's directory is
+//! watched, so a file renamed or deleted by log rotation is read to its end
+//! and the new file created at the path is picked up from its start.
+//! Truncation in place (`copytruncate`) restarts reading at the beginning.
+//! In a watched folder, only the file whose name sorts last among those
+//! matching its pattern is followed. A newer file that appears is followed
+//! from its start, such as the next day's file of a daily log, and the one
+//! followed before is read to its end and closed.
+//!
+//! A line caught half-written is held back until its newline arrives, or
+//! until the partial line timeout passes without one; the rest of a line
+//! sent on timeout keeps its number.
+//!
+//! Lines are decoded in the encoding set for the file or its folder, or the
+//! one named by a byte order mark, and bytes invalid in it are replaced.
+//!
+//! Large files are never read in full when followed: the initial content is
+//! read backwards from the end in blocks, and line numbers are estimated,
+//! and flagged as such, instead of counted.
 
 use std::collections::HashMap;
-use std::fs::File;
-use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::fs::{File, Metadata};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
-use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError, Sender};
 use std::sync::{Arc, Mutex};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use log::{debug, error, info, warn};
 use notify::{
@@ -14,17 +34,420 @@ use notify::{
 };
 
 use crate::domain::log_watching::ports::{FileWatchEvent, FileWatcher, WatchError, WatchResult};
+use crate::domain::log_watching::TextEncoding;
+
+/// Maximum number of bytes read from the end of a file when sampling it.
+const SAMPLE_TAIL_BYTES: u64 = 64 * 1024;
+
+/// Size of the blocks read backwards from the end of a file.
+const REVERSE_BLOCK_BYTES: u64 = 64 * 1024;
+
+/// Files up to this size have their lines counted when they are numbered
+/// from their end; in larger ones the count is estimated from the last block.
+const EXACT_COUNT_LIMIT: u64 = 16 * 1024 * 1024;
+
+/// How long an incomplete trailing line is held back by default.
+pub const DEFAULT_PARTIAL_LINE_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// How often held back lines are checked against the timeout.
+const PARTIAL_LINE_CHECK_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Whether followed files are kept open between reads.
+///
+/// An open handle lets the rest of a file be read after it is renamed or
+/// deleted; it is closed once that is done. Windows refuses to rename a
+/// file that is held open, so there the path is opened again for every read.
+const KEEP_HANDLE: bool = cfg!(unix);
+
+/// Read one line in `encoding`, including its line ending, onto `buf`.
+///
+/// `buf` must hold nothing or the start of the same line, so UTF-16 line
+/// endings are found on code unit boundaries. Returns the number of bytes read.
+fn read_line(
+    reader: &mut impl BufRead,
+    encoding: TextEncoding,
+    buf: &mut Vec<u8>,
+) -> std::io::Result<usize> {
+    if encoding.unit_len() == 1 {
+        return reader.read_until(b'\n', buf);
+    }
+
+    let start = buf.len();
+    loop {
+        let available = reader.fill_buf()?;
+        if available.is_empty() {
+            break;
+        }
+        let mut used = 0;
+        let mut found = false;
+        for &byte in available {
+            buf.push(byte);
+            used += 1;
+            if encoding.ends_line(buf) {
+                found = true;
+                break;
+            }
+        }
+        reader.consume(used);
+        if found {
+            break;
+        }
+    }
+    Ok(buf.len() - start)
+}
+
+/// Split bytes into lines in `encoding`, each with its line ending.
+///
+/// The last line has no line ending if the bytes stop before it.
+fn split_lines(bytes: &[u8], encoding: TextEncoding) -> Vec<&[u8]> {
+    let newline = encoding.newline();
+    let mut lines = Vec::new();
+    let mut line_start = 0;
+    let mut i = 0;
+    while i + newline.len() <= bytes.len() {
+        if bytes[i..].starts_with(newline) {
+            i += newline.len();
+            lines.push(&bytes[line_start..i]);
+            line_start = i;
+        } else {
+            i += encoding.unit_len();
+        }
+    }
+    if line_start < bytes.len() {
+        lines.push(&bytes[line_start..]);
+    }
+    lines
+}
+
+/// Read up to `count` lines ending at or before byte `end`, reading back
+/// from there in blocks.
+///
+/// Returns the lines in file order, and the number of lines before `end`
+/// if the start of the file was reached.
+fn read_last_lines(
+    file: &mut File,
+    end: u64,
+    count: usize,
+    encoding: TextEncoding,
+) -> std::io::Result<(Vec<String>, Option<usize>)> {
+    let unit = encoding.unit_len() as u64;
+    let mut start = end;
+    let mut tail = Vec::new();
+    let lines = loop {
+        let block_start = start.saturating_sub(REVERSE_BLOCK_BYTES) / unit * unit;
+        let mut block = Vec::new();
+        file.seek(SeekFrom::Start(block_start))?;
+        file.by_ref()
+            .take(start - block_start)
+            .read_to_end(&mut block)?;
+        block.append(&mut tail);
+        tail = block;
+        start = block_start;
+
+        let mut lines = split_lines(&tail, encoding);
+        if start == 0 {
+            break lines;
+        }
+        // The first line may have started before the bytes read
+        if lines.len() > count {
+            lines.remove(0);
+            break lines;
+        }
+    };
+
+    let total = (start == 0).then_some(lines.len());
+    let skip = lines.len().saturating_sub(count);
+    let lines = lines[skip..]
+        .iter()
+        .map(|line| encoding.decode_line(line))
+        .collect();
+    Ok((lines, total))
+}
+
+/// Estimate the number of lines in the first `len` bytes of a file from the
+/// average length of the lines in its last block.
+fn estimate_line_count(
+    file: &mut File,
+    len: u64,
+    encoding: TextEncoding,
+) -> std::io::Result<usize> {
+    let unit = encoding.unit_len() as u64;
+    let start = len.saturating_sub(REVERSE_BLOCK_BYTES) / unit * unit;
+    let mut block = Vec::new();
+    file.seek(SeekFrom::Start(start))?;
+    file.by_ref().take(len - start).read_to_end(&mut block)?;
+
+    let mut lines = split_lines(&block, encoding);
+    if start == 0 {
+        return Ok(lines.len());
+    }
+    // The first line started before the block
+    lines.remove(0);
+
+    let complete: Vec<&[u8]> = lines
+        .into_iter()
+        .filter(|line| encoding.ends_line(line))
+        .collect();
+    let bytes: u64 = complete.iter().map(|line| line.len() as u64).sum();
+    if bytes == 0 {
+        return Ok(1);
+    }
+    Ok(((len * complete.len() as u64 + bytes / 2) / bytes) as usize)
+}
+
+/// Count the lines in the first `len` bytes of a file.
+///
+/// Files up to [`EXACT_COUNT_LIMIT`] are counted; larger ones get an
+/// estimate, and `true` is returned with it.
+fn line_count(file: &mut File, len: u64, encoding: TextEncoding) -> std::io::Result<(usize, bool)> {
+    if len > EXACT_COUNT_LIMIT {
+        return Ok((estimate_line_count(file, len, encoding)?, true));
+    }
+
+    file.seek(SeekFrom::Start(0))?;
+    let mut reader = BufReader::new(file.by_ref().take(len));
+    let mut buf = Vec::new();
+    let mut count = 0;
+    loop {
+        buf.clear();
+        if read_line(&mut reader, encoding, &mut buf)? == 0 {
+            return Ok((count, false));
+        }
+        count += 1;
+    }
+}
+
+/// The encoding named by a byte order mark at the reader's position, if any.
+fn bom_encoding(reader: &mut impl BufRead) -> std::io::Result<Option<TextEncoding>> {
+    Ok(TextEncoding::from_bom(reader.fill_buf()?).map(|(encoding, _)| encoding))
+}
+
+/// A watched folder.
+#[derive(Debug, Clone)]
+struct WatchedFolder {
+    /// Pattern the folder's files must match to be followed.
+    pattern: glob::Pattern,
+    /// Encoding the folder's files are decoded in.
+    encoding: TextEncoding,
+    /// The file followed in the folder, kept after it is deleted.
+    followed: Option<PathBuf>,
+}
+
+impl WatchedFolder {
+    /// Checks if a matching file that is not followed yet should be: the
+    /// followed file created again, or one whose name sorts after it.
+    fn follows(&self, path: &Path) -> bool {
+        self.followed
+            .as_deref()
+            .map_or(true, |followed| path >= followed)
+    }
+}
+
+/// Identity of a file on disk, used to notice a new file at a followed path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileIdentity {
+    device: u64,
+    inode: u64,
+}
+
+impl FileIdentity {
+    /// Returns the identity of a file, where the platform exposes one.
+    #[cfg(unix)]
+    fn of(metadata: &Metadata) -> Option<Self> {
+        use std::os::unix::fs::MetadataExt;
+        Some(Self {
+            device: metadata.dev(),
+            inode: metadata.ino(),
+        })
+    }
+
+    /// Returns the identity of a file, where the platform exposes one.
+    #[cfg(not(unix))]
+    fn of(_metadata: &Metadata) -> Option<Self> {
+        None
+    }
+}
 
 /// File state tracking for detecting changes.
 #[derive(Debug)]
 struct FileState {
-    /// Last known file size.
+    /// Number of bytes of the followed file read so far.
     size: u64,
     /// Last known line number.
     line_number: usize,
+    /// Whether `line_number` is estimated, see [`EXACT_COUNT_LIMIT`].
+    approximate: bool,
+    /// Bytes read of a line whose newline has not been written yet.
+    partial: Vec<u8>,
+    /// When the held back line was first read.
+    partial_since: Option<Instant>,
+    /// Whether the next line read continues one sent before its newline.
+    continues_line: bool,
     /// Pattern for directory watching (None for single files).
-    #[allow(dead_code)]
     pattern: Option<glob::Pattern>,
+    /// Encoding set for the file.
+    encoding: TextEncoding,
+    /// Encoding named by a byte order mark at the start of the file.
+    bom: Option<TextEncoding>,
+    /// Identity of the followed file.
+    identity: Option<FileIdentity>,
+    /// Open handle on the followed file, see [`KEEP_HANDLE`].
+    handle: Option<File>,
+    /// Whether the followed file was moved away from or deleted at the path.
+    detached: bool,
+}
+
+impl FileState {
+    /// Start following the file at a path from its beginning.
+    fn open(
+        path: &Path,
+        pattern: Option<glob::Pattern>,
+        encoding: TextEncoding,
+    ) -> std::io::Result<Self> {
+        let file = File::open(path)?;
+        let identity = FileIdentity::of(&file.metadata()?);
+
+        Ok(Self {
+            size: 0,
+            line_number: 0,
+            approximate: false,
+            partial: Vec::new(),
+            partial_since: None,
+            continues_line: false,
+            pattern,
+            encoding,
+            bom: None,
+            identity,
+            handle: KEEP_HANDLE.then_some(file),
+            detached: false,
+        })
+    }
+
+    /// The encoding lines are decoded in, preferring a byte order mark.
+    fn encoding(&self) -> TextEncoding {
+        self.bom.unwrap_or(self.encoding)
+    }
+
+    /// Open a reader on the followed file, positioned after the content read so far.
+    ///
+    /// At the start of the file, its byte order mark is looked for.
+    fn reader(&mut self, path: &Path) -> std::io::Result<BufReader<File>> {
+        let file = match &self.handle {
+            Some(handle) => handle.try_clone()?,
+            None => File::open(path)?,
+        };
+        let mut reader = BufReader::new(file);
+        reader.seek(SeekFrom::Start(self.size))?;
+        if self.size == 0 {
+            self.bom = bom_encoding(&mut reader)?;
+        }
+        Ok(reader)
+    }
+
+    /// Skip the content currently in the file, numbering from its
+    /// [`line_count`].
+    fn follow_from_end(&mut self, path: &Path) -> std::io::Result<()> {
+        let mut reader = self.reader(path)?;
+        let len = reader.get_ref().metadata()?.len();
+        let (line_number, approximate) = line_count(reader.get_mut(), len, self.encoding())?;
+        self.line_number = line_number;
+        self.size = len;
+        self.approximate = approximate;
+        Ok(())
+    }
+
+    /// The byte offset after the last complete line read.
+    fn lines_end(&self) -> u64 {
+        self.size - self.partial.len() as u64
+    }
+
+    /// Number the next line read.
+    ///
+    /// The rest of a line sent on timeout keeps that line's number.
+    fn advance_line(&mut self) -> usize {
+        if !std::mem::take(&mut self.continues_line) {
+            self.line_number += 1;
+        }
+        self.line_number
+    }
+
+    /// Read the lines written since the last read, with the number of the first.
+    ///
+    /// An incomplete trailing line is held back, see [`Self::flush_partial`].
+    fn read_new_lines(&mut self, path: &Path) -> std::io::Result<(usize, Vec<String>)> {
+        let mut reader = self.reader(path)?;
+        let encoding = self.encoding();
+        let mut first_line_number = None;
+        let mut lines = Vec::new();
+        loop {
+            let mut buf = std::mem::take(&mut self.partial);
+            let read = read_line(&mut reader, encoding, &mut buf)?;
+            if read == 0 {
+                self.partial = buf;
+                break;
+            }
+            self.size += read as u64;
+            if !encoding.ends_line(&buf) {
+                // Wait for the rest of the line
+                self.partial = buf;
+                self.partial_since.get_or_insert_with(Instant::now);
+                break;
+            }
+            let line_number = self.advance_line();
+            first_line_number.get_or_insert(line_number);
+            self.partial_since = None;
+            lines.push(encoding.decode_line(&buf));
+        }
+        Ok((first_line_number.unwrap_or(self.line_number + 1), lines))
+    }
+
+    /// Take the held back line once it has waited `timeout` for its newline,
+    /// with its number.
+    ///
+    /// The rest of the line, once written, is read under the same number.
+    fn flush_partial(&mut self, timeout: Duration) -> Option<(usize, String)> {
+        if self.partial_since?.elapsed() < timeout {
+            return None;
+        }
+        self.partial_since = None;
+        let bytes = std::mem::take(&mut self.partial);
+        let line_number = self.advance_line();
+        self.continues_line = true;
+        Some((line_number, self.encoding().decode_line(&bytes)))
+    }
+
+    /// Set the encoding of the file.
+    ///
+    /// Lines are counted again when the width of a line ending changes.
+    fn set_encoding(&mut self, path: &Path, encoding: TextEncoding) -> std::io::Result<()> {
+        let recount = self.bom.is_none() && encoding.unit_len() != self.encoding.unit_len();
+        self.encoding = encoding;
+        if recount {
+            self.reset();
+            self.follow_from_end(path)?;
+        }
+        Ok(())
+    }
+
+    /// Forget everything read so far, to read the file again from its start.
+    fn reset(&mut self) {
+        self.size = 0;
+        self.line_number = 0;
+        self.approximate = false;
+        self.partial.clear();
+        self.partial_since = None;
+        self.continues_line = false;
+        self.bom = None;
+    }
+}
+
+/// Lines read from a file when it is first shown.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InitialContent {
+    /// Line numbers and lines, in file order.
+    pub lines: Vec<(usize, String)>,
+    /// Whether the line numbers are estimated rather than counted.
+    pub approximate: bool,
 }
 
 /// File watcher implementation using notify.
@@ -33,6 +456,12 @@ pub struct NotifyFileWatcher {
     watcher: RecommendedWatcher,
     /// Tracked file states.
     file_states: Arc<Mutex<HashMap<PathBuf, FileState>>>,
+    /// Watched folders, with the pattern their files must match.
+    folders: Arc<Mutex<HashMap<PathBuf, WatchedFolder>>>,
+    /// Directories watched through notify, with the number of watches using each.
+    watched_dirs: HashMap<PathBuf, usize>,
+    /// How long an incomplete trailing line waits for its newline.
+    partial_line_timeout: Arc<Mutex<Duration>>,
     /// Event sender for notifying about file changes.
     event_tx: Sender<FileWatchEvent>,
     /// Event receiver for consuming file changes.
@@ -47,12 +476,23 @@ impl NotifyFileWatcher {
 
         let file_states: Arc<Mutex<HashMap<PathBuf, FileState>>> =
             Arc::new(Mutex::new(HashMap::new()));
+        let folders: Arc<Mutex<HashMap<PathBuf, WatchedFolder>>> =
+            Arc::new(Mutex::new(HashMap::new()));
+        let partial_line_timeout = Arc::new(Mutex::new(DEFAULT_PARTIAL_LINE_TIMEOUT));
         let states_clone = Arc::clone(&file_states);
+        let folders_clone = Arc::clone(&folders);
+        let timeout_clone = Arc::clone(&partial_line_timeout);
         let event_tx_clone = event_tx.clone();
 
         // Spawn a thread to handle notify events
         std::thread::spawn(move || {
-            Self::process_notify_events(notify_rx, states_clone, event_tx_clone);
+            Self::process_notify_events(
+                notify_rx,
+                states_clone,
+                folders_clone,
+                timeout_clone,
+                event_tx_clone,
+            );
         });
 
         let watcher = RecommendedWatcher::new(
@@ -68,6 +508,9 @@ impl NotifyFileWatcher {
         Ok(Self {
             watcher,
             file_states,
+            folders,
+            watched_dirs: HashMap::new(),
+            partial_line_timeout,
             event_tx,
             event_rx: Some(event_rx),
         })
@@ -83,21 +526,42 @@ impl NotifyFileWatcher {
         self.event_tx.clone()
     }
 
+    /// Set how long an incomplete trailing line waits for its newline
+    /// before it is sent as it is.
+    pub fn set_partial_line_timeout(&self, timeout: Duration) {
+        *self.partial_line_timeout.lock().unwrap() = timeout;
+    }
+
+    /// How long an incomplete trailing line waits for its newline.
+    pub fn partial_line_timeout(&self) -> Duration {
+        *self.partial_line_timeout.lock().unwrap()
+    }
+
     /// Process notify events and convert them to FileWatchEvents.
+    ///
+    /// Held back lines are checked between events, and at least every
+    /// [`PARTIAL_LINE_CHECK_INTERVAL`] while no events arrive.
     fn process_notify_events(
         rx: Receiver<Result<Event, notify::Error>>,
         file_states: Arc<Mutex<HashMap<PathBuf, FileState>>>,
+        folders: Arc<Mutex<HashMap<PathBuf, WatchedFolder>>>,
+        partial_line_timeout: Arc<Mutex<Duration>>,
         event_tx: Sender<FileWatchEvent>,
     ) {
-        for result in rx {
-            match result {
-                Ok(event) => {
-                    Self::handle_notify_event(event, &file_states, &event_tx);
+        loop {
+            match rx.recv_timeout(PARTIAL_LINE_CHECK_INTERVAL) {
+                Ok(Ok(event)) => {
+                    Self::handle_notify_event(event, &file_states, &folders, &event_tx);
                 }
-                Err(e) => {
+                Ok(Err(e)) => {
                     error!("Notify error: {}", e);
                 }
+                Err(RecvTimeoutError::Timeout) => {}
+                Err(RecvTimeoutError::Disconnected) => break,
             }
+
+            let timeout = *partial_line_timeout.lock().unwrap();
+            Self::flush_partial_lines(&file_states, timeout, &event_tx);
         }
     }
 
@@ -105,10 +569,18 @@ impl NotifyFileWatcher {
     fn handle_notify_event(
         event: Event,
         file_states: &Arc<Mutex<HashMap<PathBuf, FileState>>>,
+        folders: &Arc<Mutex<HashMap<PathBuf, WatchedFolder>>>,
         event_tx: &Sender<FileWatchEvent>,
     ) {
         debug!("Notify event: {:?}", event);
 
+        let is_tracked = |path: &PathBuf| {
+            file_states
+                .lock()
+                .map(|states| states.contains_key(path))
+                .unwrap_or(false)
+        };
+
         for path in event.paths {
             // Skip if this is a directory
             if path.is_dir() {
@@ -117,25 +589,35 @@ impl NotifyFileWatcher {
 
             match event.kind {
                 EventKind::Create(_) => {
-                    if let Err(e) = event_tx.send(FileWatchEvent::FileCreated { path }) {
-                        error!("Failed to send FileCreated event: {}", e);
+                    if is_tracked(&path) {
+                        // A new file at a followed path replaces the old one
+                        Self::handle_file_modification(&path, file_states, folders, event_tx);
+                        Self::send_created(&path, event_tx);
+                    } else if let Some(folder) = Self::watched_folder(&path, folders) {
+                        Self::follow_new_file(&path, folder, file_states, folders, event_tx);
+                    } else {
+                        Self::send_created(&path, event_tx);
                     }
                 }
                 EventKind::Remove(_) => {
-                    // Remove from file states
-                    if let Ok(mut states) = file_states.lock() {
-                        states.remove(&path);
-                    }
+                    Self::detach(&path, file_states, event_tx);
+                    Self::forget_folder_file(&path, file_states);
                     if let Err(e) = event_tx.send(FileWatchEvent::FileDeleted { path }) {
                         error!("Failed to send FileDeleted event: {}", e);
                     }
                 }
                 EventKind::Modify(ModifyKind::Data(_)) | EventKind::Modify(ModifyKind::Any) => {
-                    Self::handle_file_modification(&path, file_states, event_tx);
+                    Self::handle_file_modification(&path, file_states, folders, event_tx);
                 }
                 EventKind::Modify(ModifyKind::Name(_)) => {
-                    // File renamed - we'll get separate Create/Remove events
-                    debug!("File renamed: {:?}", path);
+                    // Renamed away from a followed path, or onto one
+                    if path.exists() {
+                        if is_tracked(&path) {
+                            Self::handle_file_modification(&path, file_states, folders, event_tx);
+                        }
+                    } else {
+                        Self::detach(&path, file_states, event_tx);
+                    }
                 }
                 _ => {
                     debug!("Unhandled event kind: {:?}", event.kind);
@@ -144,10 +626,131 @@ impl NotifyFileWatcher {
         }
     }
 
+    /// Send lines just read from a followed file.
+    fn send_lines(
+        path: &Path,
+        first_line_number: usize,
+        lines: Vec<String>,
+        state: &FileState,
+        event_tx: &Sender<FileWatchEvent>,
+    ) {
+        if lines.is_empty() {
+            return;
+        }
+        if let Err(e) = event_tx.send(FileWatchEvent::ContentAppended {
+            path: path.to_path_buf(),
+            lines,
+            first_line_number,
+            approximate: state.approximate,
+        }) {
+            error!("Failed to send ContentAppended event: {}", e);
+        }
+    }
+
+    /// Send an error for a file.
+    fn send_error(path: &Path, message: String, event_tx: &Sender<FileWatchEvent>) {
+        if let Err(e) = event_tx.send(FileWatchEvent::Error {
+            path: path.to_path_buf(),
+            message,
+        }) {
+            error!("Failed to send Error event: {}", e);
+        }
+    }
+
+    /// Read and send the lines written to a followed file since the last read.
+    fn read_and_send(path: &Path, state: &mut FileState, event_tx: &Sender<FileWatchEvent>) {
+        match state.read_new_lines(path) {
+            Ok((first_line_number, lines)) => {
+                Self::send_lines(path, first_line_number, lines, state, event_tx)
+            }
+            Err(e) => Self::send_error(path, format!("Failed to read file: {}", e), event_tx),
+        }
+    }
+
+    /// Send the held back line of a file once it has waited `timeout`.
+    fn flush_and_send(
+        path: &Path,
+        state: &mut FileState,
+        timeout: Duration,
+        event_tx: &Sender<FileWatchEvent>,
+    ) {
+        if let Some((line_number, line)) = state.flush_partial(timeout) {
+            Self::send_lines(path, line_number, vec![line], state, event_tx);
+        }
+    }
+
+    /// Send the held back lines that have waited `timeout` for their newline.
+    fn flush_partial_lines(
+        file_states: &Arc<Mutex<HashMap<PathBuf, FileState>>>,
+        timeout: Duration,
+        event_tx: &Sender<FileWatchEvent>,
+    ) {
+        let mut states = file_states.lock().unwrap();
+        for (path, state) in states.iter_mut() {
+            Self::flush_and_send(path, state, timeout, event_tx);
+        }
+    }
+
+    /// Note that a followed file left its path, reading what is left of it.
+    ///
+    /// The path stays followed, so a new file created there is picked up.
+    fn detach(
+        path: &Path,
+        file_states: &Arc<Mutex<HashMap<PathBuf, FileState>>>,
+        event_tx: &Sender<FileWatchEvent>,
+    ) {
+        let mut states = file_states.lock().unwrap();
+        let Some(state) = states.get_mut(path) else {
+            return;
+        };
+        if state.detached {
+            return;
+        }
+
+        info!("File moved or deleted: {:?}", path);
+        state.detached = true;
+        Self::finish(path, state, event_tx);
+        state.handle = None;
+    }
+
+    /// Stop tracking a deleted file of a watched folder.
+    ///
+    /// The folder still picks the file up again if it is created anew.
+    fn forget_folder_file(path: &Path, file_states: &Arc<Mutex<HashMap<PathBuf, FileState>>>) {
+        let mut states = file_states.lock().unwrap();
+        if states
+            .get(path)
+            .is_some_and(|state| state.pattern.is_some())
+        {
+            states.remove(path);
+        }
+    }
+
+    /// Switch a followed path over to the new file created there.
+    ///
+    /// The rest of the old file is read first, if it is still open.
+    fn rotate(path: &Path, state: &mut FileState, event_tx: &Sender<FileWatchEvent>) {
+        Self::finish(path, state, event_tx);
+
+        let new_state = match FileState::open(path, state.pattern.take(), state.encoding) {
+            Ok(new_state) => new_state,
+            Err(e) => {
+                warn!("Failed to open rotated file {:?}: {}", path, e);
+                return;
+            }
+        };
+        *state = new_state;
+
+        info!("File rotated: {:?}", path);
+        Self::send_rotated(path, event_tx);
+        Self::read_and_send(path, state, event_tx);
+    }
+
     /// Handle a file modification event by reading new content.
     fn handle_file_modification(
         path: &PathBuf,
         file_states: &Arc<Mutex<HashMap<PathBuf, FileState>>>,
+        folders: &Arc<Mutex<HashMap<PathBuf, WatchedFolder>>>,
         event_tx: &Sender<FileWatchEvent>,
     ) {
         let metadata = match std::fs::metadata(path) {
@@ -158,127 +761,310 @@ impl NotifyFileWatcher {
             }
         };
 
-        let current_size = metadata.len();
-
-        let (previous_size, mut line_number) = {
-            let states = file_states.lock().unwrap();
-            states
-                .get(path)
-                .map(|s| (s.size, s.line_number))
-                .unwrap_or((0, 0))
+        let mut states = file_states.lock().unwrap();
+        let Some(state) = states.get_mut(path) else {
+            drop(states);
+            if let Some(folder) = Self::watched_folder(path, folders) {
+                Self::follow_new_file(path, folder, file_states, folders, event_tx);
+            }
+            return;
         };
 
-        if current_size < previous_size {
-            // File was truncated
+        if state.detached || FileIdentity::of(&metadata) != state.identity {
+            Self::rotate(path, state, event_tx);
+            return;
+        }
+
+        if metadata.len() < state.size {
+            // File was truncated, e.g. by copytruncate; read it again from the start
             info!("File truncated: {:?}", path);
-            if let Ok(mut states) = file_states.lock() {
-                if let Some(state) = states.get_mut(path) {
-                    state.size = 0;
-                    state.line_number = 0;
-                }
-            }
+            state.reset();
             if let Err(e) = event_tx.send(FileWatchEvent::FileTruncated { path: path.clone() }) {
                 error!("Failed to send FileTruncated event: {}", e);
             }
+        }
+
+        if metadata.len() > state.size {
+            Self::read_and_send(path, state, event_tx);
+        }
+    }
+
+    /// Returns the watched folder a file is in, if the file matches its pattern.
+    fn watched_folder(
+        path: &Path,
+        folders: &Arc<Mutex<HashMap<PathBuf, WatchedFolder>>>,
+    ) -> Option<WatchedFolder> {
+        let (parent, file_name) = (path.parent()?, path.file_name()?);
+        folders
+            .lock()
+            .unwrap()
+            .get(parent)
+            .filter(|folder| folder.pattern.matches(file_name.to_string_lossy().as_ref()))
+            .cloned()
+    }
+
+    /// Read the rest of a file that is not followed any longer.
+    fn finish(path: &Path, state: &mut FileState, event_tx: &Sender<FileWatchEvent>) {
+        if state.handle.is_some() {
+            Self::read_and_send(path, state, event_tx);
+        }
+        // Nothing more is read from the old file, so its last line is complete
+        Self::flush_and_send(path, state, Duration::ZERO, event_tx);
+    }
+
+    /// Start following a file that appeared in a watched folder, from its start,
+    /// if it is newer than the one followed so far.
+    ///
+    /// The file followed before is read to its end and dropped. `FileCreated`
+    /// is sent before the new file's first lines, which are numbered from 1;
+    /// the followed file created again is announced with `FileRotated`.
+    fn follow_new_file(
+        path: &Path,
+        folder: WatchedFolder,
+        file_states: &Arc<Mutex<HashMap<PathBuf, FileState>>>,
+        folders: &Arc<Mutex<HashMap<PathBuf, WatchedFolder>>>,
+        event_tx: &Sender<FileWatchEvent>,
+    ) {
+        let mut states = file_states.lock().unwrap();
+        if states.contains_key(path) || !folder.follows(path) {
             return;
         }
+        let mut state = match FileState::open(path, Some(folder.pattern), folder.encoding) {
+            Ok(state) => state,
+            Err(e) => {
+                Self::send_error(path, format!("Failed to open file: {}", e), event_tx);
+                return;
+            }
+        };
 
-        if current_size > previous_size {
-            // New content appended
-            match File::open(path) {
-                Ok(file) => {
-                    let mut reader = BufReader::new(file);
-                    if let Err(e) = reader.seek(SeekFrom::Start(previous_size)) {
-                        warn!("Failed to seek in file {:?}: {}", path, e);
-                        return;
-                    }
+        let created_again = folder.followed.as_deref() == Some(path);
+        if let Some(previous) = folder.followed.filter(|_| !created_again) {
+            if let Some(mut previous_state) = states.remove(&previous) {
+                Self::finish(&previous, &mut previous_state, event_tx);
+            }
+        }
+        if let Some(parent) = path.parent() {
+            if let Some(watched) = folders.lock().unwrap().get_mut(parent) {
+                watched.followed = Some(path.to_path_buf());
+            }
+        }
 
-                    let mut new_content = String::new();
-                    for line_result in reader.lines() {
-                        match line_result {
-                            Ok(line) => {
-                                line_number += 1;
-                                if !new_content.is_empty() {
-                                    new_content.push('\n');
-                                }
-                                new_content.push_str(&line);
-                            }
-                            Err(e) => {
-                                warn!("Error reading line from {:?}: {}", path, e);
-                                break;
-                            }
-                        }
-                    }
+        info!("Following new file: {:?}", path);
+        if created_again {
+            Self::send_rotated(path, event_tx);
+        } else {
+            Self::send_created(path, event_tx);
+        }
+        Self::read_and_send(path, &mut state, event_tx);
+        states.insert(path.to_path_buf(), state);
+    }
 
-                    if !new_content.is_empty() {
-                        if let Err(e) = event_tx.send(FileWatchEvent::ContentAppended {
-                            path: path.clone(),
-                            content: new_content,
-                            line_number,
-                        }) {
-                            error!("Failed to send ContentAppended event: {}", e);
-                        }
-                    }
-                }
-                Err(e) => {
-                    if let Err(e2) = event_tx.send(FileWatchEvent::Error {
-                        path: path.clone(),
-                        message: format!("Failed to open file: {}", e),
-                    }) {
-                        error!("Failed to send Error event: {}", e2);
-                    }
-                    return;
+    /// Send a `FileRotated` event.
+    fn send_rotated(path: &Path, event_tx: &Sender<FileWatchEvent>) {
+        if let Err(e) = event_tx.send(FileWatchEvent::FileRotated {
+            path: path.to_path_buf(),
+        }) {
+            error!("Failed to send FileRotated event: {}", e);
+        }
+    }
+
+    /// Send a `FileCreated` event.
+    fn send_created(path: &Path, event_tx: &Sender<FileWatchEvent>) {
+        if let Err(e) = event_tx.send(FileWatchEvent::FileCreated {
+            path: path.to_path_buf(),
+        }) {
+            error!("Failed to send FileCreated event: {}", e);
+        }
+    }
+
+    /// Watch a directory through notify, sharing the watch between callers.
+    fn watch_dir(&mut self, dir: &Path) -> WatchResult<()> {
+        if let Some(count) = self.watched_dirs.get_mut(dir) {
+            *count += 1;
+            return Ok(());
+        }
+
+        self.watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| WatchError::WatcherError(e.to_string()))?;
+        self.watched_dirs.insert(dir.to_path_buf(), 1);
+        Ok(())
+    }
+
+    /// Release a directory watch, removing it when no caller needs it anymore.
+    fn release_dir(&mut self, dir: &Path) {
+        match self.watched_dirs.get_mut(dir) {
+            Some(count) if *count > 1 => *count -= 1,
+            Some(_) => {
+                self.watched_dirs.remove(dir);
+                if let Err(e) = self.watcher.unwatch(dir) {
+                    warn!("Failed to unwatch {:?}: {}", dir, e);
                 }
             }
+            None => {}
+        }
+    }
 
-            // Update file state
-            if let Ok(mut states) = file_states.lock() {
-                if let Some(state) = states.get_mut(path) {
-                    state.size = current_size;
-                    state.line_number = line_number;
-                }
+    /// Returns the directory a file is in.
+    fn parent_dir(path: &Path) -> PathBuf {
+        match path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        }
+    }
+
+    /// Open a file for reading, mapping common errors.
+    fn open_file(path: &Path) -> WatchResult<File> {
+        File::open(path).map_err(|e| {
+            if e.kind() == std::io::ErrorKind::NotFound {
+                WatchError::FileNotFound(path.to_path_buf())
+            } else if e.kind() == std::io::ErrorKind::PermissionDenied {
+                WatchError::PermissionDenied(path.to_path_buf())
+            } else {
+                WatchError::IoError(e)
             }
+        })
+    }
+
+    /// The encoding set for a followed file, or for the folder it is in.
+    fn configured_encoding(&self, path: &Path) -> TextEncoding {
+        if let Some(state) = self.file_states.lock().unwrap().get(path) {
+            return state.encoding;
         }
+        path.parent()
+            .and_then(|parent| {
+                let folders = self.folders.lock().unwrap();
+                folders.get(parent).map(|folder| folder.encoding)
+            })
+            .unwrap_or_default()
     }
 
     /// Read the initial content of a file.
+    ///
+    /// With a limit, only the last lines are read, backwards from the end.
+    /// For a followed file they end where following it has read up to, and
+    /// are numbered back from its line count; otherwise from the
+    /// [`line_count`] of the file, unless the whole file was read.
     pub fn read_initial_content(
         &self,
         path: &PathBuf,
         max_lines: Option<usize>,
-    ) -> WatchResult<Vec<(usize, String)>> {
-        let file = File::open(path).map_err(|e| {
-            if e.kind() == std::io::ErrorKind::NotFound {
-                WatchError::FileNotFound(path.clone())
-            } else if e.kind() == std::io::ErrorKind::PermissionDenied {
-                WatchError::PermissionDenied(path.clone())
-            } else {
-                WatchError::IoError(e)
-            }
-        })?;
+    ) -> WatchResult<InitialContent> {
+        let mut file = Self::open_file(path)?;
+        let encoding =
+            bom_encoding(&mut BufReader::new(&file))?.unwrap_or(self.configured_encoding(path));
+
+        let Some(max) = max_lines else {
+            return Self::read_all_lines(path, file, encoding);
+        };
+
+        let followed = self
+            .file_states
+            .lock()
+            .unwrap()
+            .get(path)
+            .map(|state| (state.lines_end(), state.line_number, state.approximate));
+        let end = match followed {
+            Some((end, _, _)) => end,
+            None => file.metadata()?.len(),
+        };
+
+        let (lines, total) = read_last_lines(&mut file, end, max, encoding)?;
+        let (last, approximate) = match (followed, total) {
+            (Some((_, line_number, approximate)), _) => (line_number, approximate),
+            (None, Some(total)) => (total, false),
+            (None, None) => line_count(&mut file, end, encoding)?,
+        };
 
-        let reader = BufReader::new(file);
+        let first = last.max(lines.len()) - lines.len() + 1;
+        Ok(InitialContent {
+            lines: (first..).zip(lines).collect(),
+            approximate,
+        })
+    }
+
+    /// Read all lines of a file, numbered from 1.
+    fn read_all_lines(
+        path: &Path,
+        mut file: File,
+        encoding: TextEncoding,
+    ) -> WatchResult<InitialContent> {
+        file.seek(SeekFrom::Start(0))?;
+        let mut reader = BufReader::new(file);
         let mut lines: Vec<(usize, String)> = Vec::new();
+        let mut buf = Vec::new();
 
-        for (idx, line_result) in reader.lines().enumerate() {
-            match line_result {
-                Ok(line) => {
-                    lines.push((idx + 1, line));
-                }
+        loop {
+            buf.clear();
+            match read_line(&mut reader, encoding, &mut buf) {
+                Ok(0) => break,
+                Ok(_) => lines.push((lines.len() + 1, encoding.decode_line(&buf))),
                 Err(e) => {
-                    warn!("Error reading line {} from {:?}: {}", idx + 1, path, e);
+                    warn!(
+                        "Error reading line {} from {:?}: {}",
+                        lines.len() + 1,
+                        path,
+                        e
+                    );
                     break;
                 }
             }
         }
 
-        // If max_lines is specified, keep only the last N lines
-        if let Some(max) = max_lines {
-            if lines.len() > max {
-                lines = lines.split_off(lines.len() - max);
+        Ok(InitialContent {
+            lines,
+            approximate: false,
+        })
+    }
+
+    /// Read up to `count` lines from the start and from the end of a file.
+    ///
+    /// Used to sample a file for format detection without reading all of it.
+    /// The end of the file is read from at most the last 64 KiB.
+    pub fn read_sample(&self, path: &Path, count: usize) -> WatchResult<Vec<String>> {
+        let mut file = Self::open_file(path)?;
+        let size = file.metadata()?.len();
+
+        let mut lines = Vec::new();
+        let mut head_bytes = 0u64;
+        let encoding;
+        {
+            let mut reader = BufReader::new(&file);
+            encoding = bom_encoding(&mut reader)?.unwrap_or(self.configured_encoding(path));
+            let mut buf = Vec::new();
+            while lines.len() < count {
+                buf.clear();
+                let read = read_line(&mut reader, encoding, &mut buf)?;
+                if read == 0 {
+                    break;
+                }
+                head_bytes += read as u64;
+                lines.push(encoding.decode_line(&buf));
             }
         }
 
+        if head_bytes >= size {
+            return Ok(lines);
+        }
+
+        // Start on a code unit boundary
+        let unit = encoding.unit_len() as u64;
+        let tail_start = size.saturating_sub(SAMPLE_TAIL_BYTES).max(head_bytes) / unit * unit;
+        file.seek(SeekFrom::Start(tail_start))?;
+        let mut tail = Vec::new();
+        file.read_to_end(&mut tail)?;
+
+        let mut tail_lines: Vec<String> = split_lines(&tail, encoding)
+            .iter()
+            .map(|line| encoding.decode_line(line))
+            .collect();
+        // The first line is partial unless the tail starts right after the head.
+        if tail_start > head_bytes && !tail_lines.is_empty() {
+            tail_lines.remove(0);
+        }
+        let skip = tail_lines.len().saturating_sub(count);
+        lines.extend(tail_lines.drain(skip..));
+
         Ok(lines)
     }
 }
@@ -301,32 +1087,17 @@ impl FileWatcher for NotifyFileWatcher {
             }
         }
 
-        // Get initial file size
-        let metadata = std::fs::metadata(&path)?;
-        let initial_size = metadata.len();
-
-        // Count lines in the file
-        let line_count = {
-            let file = File::open(&path)?;
-            BufReader::new(file).lines().count()
-        };
+        // Follow from the current end of the file
+        let mut state = FileState::open(&path, None, TextEncoding::default())?;
+        state.follow_from_end(&path)?;
 
-        // Add to watch list
-        self.watcher
-            .watch(&path, RecursiveMode::NonRecursive)
-            .map_err(|e| WatchError::WatcherError(e.to_string()))?;
+        // Watch the directory, so the file is still followed after rotation
+        self.watch_dir(&Self::parent_dir(&path))?;
 
         // Track file state
         {
             let mut states = self.file_states.lock().unwrap();
-            states.insert(
-                path.clone(),
-                FileState {
-                    size: initial_size,
-                    line_number: line_count,
-                    pattern: None,
-                },
-            );
+            states.insert(path.clone(), state);
         }
 
         info!("Started watching file: {:?}", path);
@@ -345,38 +1116,44 @@ impl FileWatcher for NotifyFileWatcher {
         let glob_pattern = glob::Pattern::new(pattern)
             .map_err(|e| WatchError::WatcherError(format!("Invalid pattern: {}", e)))?;
 
-        // Add to watch list
-        self.watcher
-            .watch(&path, RecursiveMode::NonRecursive)
-            .map_err(|e| WatchError::WatcherError(e.to_string()))?;
-
-        // Find existing files matching the pattern
+        // Follow the matching file whose name sorts last, as the folder source shows it
+        let mut latest = None;
         for entry in std::fs::read_dir(&path)? {
-            let entry = entry?;
-            let file_path = entry.path();
-
-            if file_path.is_file() {
-                if let Some(file_name) = file_path.file_name() {
-                    if glob_pattern.matches(file_name.to_string_lossy().as_ref()) {
-                        let metadata = std::fs::metadata(&file_path)?;
-                        let line_count = {
-                            let file = File::open(&file_path)?;
-                            BufReader::new(file).lines().count()
-                        };
-
-                        let mut states = self.file_states.lock().unwrap();
-                        states.insert(
-                            file_path.clone(),
-                            FileState {
-                                size: metadata.len(),
-                                line_number: line_count,
-                                pattern: Some(glob_pattern.clone()),
-                            },
-                        );
-                    }
-                }
+            let file_path = entry?.path();
+            let matches = file_path.file_name().is_some_and(|file_name| {
+                glob_pattern.matches(file_name.to_string_lossy().as_ref())
+            });
+            if matches && file_path.is_file() && latest.as_ref() < Some(&file_path) {
+                latest = Some(file_path);
+            }
+        }
+        let state = match &latest {
+            Some(file_path) => {
+                let mut state = FileState::open(
+                    file_path,
+                    Some(glob_pattern.clone()),
+                    TextEncoding::default(),
+                )?;
+                state.follow_from_end(file_path)?;
+                Some(state)
             }
+            None => None,
+        };
+
+        // Add to watch list
+        self.watch_dir(&path)?;
+        if let (Some(file_path), Some(state)) = (&latest, state) {
+            let mut states = self.file_states.lock().unwrap();
+            states.insert(file_path.clone(), state);
         }
+        self.folders.lock().unwrap().insert(
+            path.clone(),
+            WatchedFolder {
+                pattern: glob_pattern,
+                encoding: TextEncoding::default(),
+                followed: latest,
+            },
+        );
 
         info!(
             "Started watching directory: {:?} with pattern: {}",
@@ -385,21 +1162,40 @@ impl FileWatcher for NotifyFileWatcher {
         Ok(())
     }
 
-    fn unwatch(&mut self, path: &Path) -> WatchResult<()> {
-        {
-            let states = self.file_states.lock().unwrap();
-            if !states.contains_key(path) {
-                return Err(WatchError::NotWatching(path.to_path_buf()));
+    fn set_encoding(&mut self, path: &Path, encoding: TextEncoding) -> WatchResult<()> {
+        let mut states = self.file_states.lock().unwrap();
+        let mut folders = self.folders.lock().unwrap();
+        if let Some(folder) = folders.get_mut(path) {
+            folder.encoding = encoding;
+            for (file, state) in states.iter_mut() {
+                if state.pattern.is_some() && file.parent() == Some(path) {
+                    state.set_encoding(file, encoding)?;
+                }
             }
+        } else {
+            states
+                .get_mut(path)
+                .ok_or_else(|| WatchError::NotWatching(path.to_path_buf()))?
+                .set_encoding(path, encoding)?;
         }
 
-        self.watcher
-            .unwatch(path)
-            .map_err(|e| WatchError::WatcherError(e.to_string()))?;
+        info!("Decoding {:?} as {}", path, encoding);
+        Ok(())
+    }
 
-        {
+    fn unwatch(&mut self, path: &Path) -> WatchResult<()> {
+        let is_folder = self.folders.lock().unwrap().remove(path).is_some();
+        if is_folder {
             let mut states = self.file_states.lock().unwrap();
-            states.remove(path);
+            states.retain(|file, state| state.pattern.is_none() || file.parent() != Some(path));
+            drop(states);
+            self.release_dir(path);
+        } else {
+            let removed = self.file_states.lock().unwrap().remove(path);
+            if removed.is_none() {
+                return Err(WatchError::NotWatching(path.to_path_buf()));
+            }
+            self.release_dir(&Self::parent_dir(path));
         }
 
         info!("Stopped watching: {:?}", path);
@@ -407,33 +1203,27 @@ impl FileWatcher for NotifyFileWatcher {
     }
 
     fn unwatch_all(&mut self) {
-        let paths: Vec<PathBuf> = {
-            let states = self.file_states.lock().unwrap();
-            states.keys().cloned().collect()
-        };
-
-        for path in paths {
-            if let Err(e) = self.watcher.unwatch(&path) {
-                warn!("Failed to unwatch {:?}: {}", path, e);
+        for dir in std::mem::take(&mut self.watched_dirs).into_keys() {
+            if let Err(e) = self.watcher.unwatch(&dir) {
+                warn!("Failed to unwatch {:?}: {}", dir, e);
             }
         }
 
-        {
-            let mut states = self.file_states.lock().unwrap();
-            states.clear();
-        }
+        self.file_states.lock().unwrap().clear();
+        self.folders.lock().unwrap().clear();
 
         info!("Stopped watching all files");
     }
 
     fn is_watching(&self, path: &Path) -> bool {
         let states = self.file_states.lock().unwrap();
-        states.contains_key(path)
+        states.contains_key(path) || self.folders.lock().unwrap().contains_key(path)
     }
 
     fn watched_paths(&self) -> Vec<PathBuf> {
         let states = self.file_states.lock().unwrap();
-        states.keys().cloned().collect()
+        let folders = self.folders.lock().unwrap();
+        states.keys().chain(folders.keys()).cloned().collect()
     }
 }
 
@@ -518,9 +1308,393 @@ mod tests {
         let event = rx.recv_timeout(Duration::from_secs(2));
         assert!(event.is_ok(), "Should receive event for appended content");
 
-        if let Ok(FileWatchEvent::ContentAppended { content, .. }) = event {
-            assert!(content.contains("New line"));
+        if let Ok(FileWatchEvent::ContentAppended { lines, .. }) = event {
+            assert_eq!(lines, ["New line"]);
+        }
+    }
+
+    /// Collect events until one matches, giving up after a few seconds.
+    fn collect_until(
+        rx: &Receiver<FileWatchEvent>,
+        done: impl Fn(&FileWatchEvent) -> bool,
+    ) -> Vec<FileWatchEvent> {
+        let mut events = Vec::new();
+        while let Ok(event) = rx.recv_timeout(Duration::from_secs(5)) {
+            let finished = done(&event);
+            events.push(event);
+            if finished {
+                break;
+            }
         }
+        events
+    }
+
+    /// Find the number of the first line appended that contains `text`.
+    fn appended(events: &[FileWatchEvent], text: &str) -> Option<usize> {
+        events.iter().find_map(|event| match event {
+            FileWatchEvent::ContentAppended {
+                lines,
+                first_line_number,
+                ..
+            } => lines
+                .iter()
+                .position(|line| line.contains(text))
+                .map(|i| first_line_number + i),
+            _ => None,
+        })
+    }
+
+    #[test]
+    fn test_follow_renamed_file() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("app.log");
+        {
+            let mut file = File::create(&file_path).unwrap();
+            writeln!(file, "Line 1").unwrap();
+        }
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        let rx = watcher.take_event_receiver().unwrap();
+        watcher.watch_file(file_path.clone()).unwrap();
+
+        thread::sleep(Duration::from_millis(50));
+        {
+            let mut file = OpenOptions::new().append(true).open(&file_path).unwrap();
+            writeln!(file, "Line 2").unwrap();
+        }
+        std::fs::rename(&file_path, dir.path().join("app.log.1")).unwrap();
+        {
+            let mut file = File::create(&file_path).unwrap();
+            writeln!(file, "New line 1").unwrap();
+        }
+
+        let events = collect_until(&rx, |event| {
+            appended(std::slice::from_ref(event), "New line 1").is_some()
+        });
+
+        assert!(events.iter().any(
+            |event| matches!(event, FileWatchEvent::FileRotated { path } if *path == file_path)
+        ));
+        assert!(!events
+            .iter()
+            .any(|event| matches!(event, FileWatchEvent::Error { .. })));
+        assert_eq!(appended(&events, "New line 1"), Some(1));
+        if KEEP_HANDLE {
+            assert_eq!(appended(&events, "Line 2"), Some(2));
+        }
+        assert!(watcher.is_watching(&file_path));
+    }
+
+    /// Wait up to a few seconds for a condition to hold.
+    fn wait_until(condition: impl Fn() -> bool) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while Instant::now() < deadline {
+            if condition() {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(20));
+        }
+        false
+    }
+
+    #[test]
+    fn test_close_file_renamed_away() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("app.log");
+        {
+            let mut file = File::create(&file_path).unwrap();
+            writeln!(file, "Line 1").unwrap();
+        }
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        watcher.watch_file(file_path.clone()).unwrap();
+
+        thread::sleep(Duration::from_millis(50));
+        std::fs::rename(&file_path, dir.path().join("app.log.1")).unwrap();
+
+        let detached = || {
+            let states = watcher.file_states.lock().unwrap();
+            states.get(&file_path).is_some_and(|state| state.detached)
+        };
+        assert!(wait_until(detached));
+        assert!(watcher.file_states.lock().unwrap()[&file_path]
+            .handle
+            .is_none());
+        assert!(watcher.is_watching(&file_path));
+    }
+
+    #[test]
+    fn test_follow_truncated_file() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("app.log");
+        {
+            let mut file = File::create(&file_path).unwrap();
+            for i in 1..=5 {
+                writeln!(file, "Old line {}", i).unwrap();
+            }
+        }
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        let rx = watcher.take_event_receiver().unwrap();
+        watcher.watch_file(file_path.clone()).unwrap();
+
+        thread::sleep(Duration::from_millis(50));
+        {
+            let mut file = OpenOptions::new().write(true).open(&file_path).unwrap();
+            file.set_len(0).unwrap();
+            writeln!(file, "After").unwrap();
+        }
+
+        let events = collect_until(&rx, |event| {
+            appended(std::slice::from_ref(event), "After").is_some()
+        });
+
+        assert!(events
+            .iter()
+            .any(|event| matches!(event, FileWatchEvent::FileTruncated { .. })));
+        assert_eq!(appended(&events, "After"), Some(1));
+    }
+
+    #[test]
+    fn test_unwatch_folder() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("a.log")).unwrap();
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        watcher
+            .watch_directory(dir.path().to_path_buf(), "*.log")
+            .unwrap();
+        assert!(watcher.is_watching(dir.path()));
+
+        assert!(watcher.unwatch(dir.path()).is_ok());
+        assert!(!watcher.is_watching(dir.path()));
+        assert!(!watcher.is_watching(&dir.path().join("a.log")));
+    }
+
+    #[test]
+    fn test_hold_back_partial_line() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("app.log");
+        {
+            let mut file = File::create(&file_path).unwrap();
+            writeln!(file, "Line 1").unwrap();
+        }
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        let rx = watcher.take_event_receiver().unwrap();
+        watcher.watch_file(file_path.clone()).unwrap();
+
+        thread::sleep(Duration::from_millis(50));
+        let mut file = OpenOptions::new().append(true).open(&file_path).unwrap();
+        write!(file, "Half a").unwrap();
+        file.flush().unwrap();
+        thread::sleep(Duration::from_millis(100));
+        write!(file, " line\nLine 3\n").unwrap();
+
+        let events = collect_until(&rx, |event| {
+            appended(std::slice::from_ref(event), "Line 3").is_some()
+        });
+
+        assert_eq!(appended(&events, "Line 3"), Some(3));
+        let batches: Vec<&[String]> = events
+            .iter()
+            .filter_map(|event| match event {
+                FileWatchEvent::ContentAppended { lines, .. } => Some(lines.as_slice()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(batches, [["Half a line", "Line 3"]]);
+    }
+
+    #[test]
+    fn test_flush_partial_line_after_timeout() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("app.log");
+        File::create(&file_path).unwrap();
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        watcher.set_partial_line_timeout(Duration::from_millis(100));
+        let rx = watcher.take_event_receiver().unwrap();
+        watcher.watch_file(file_path.clone()).unwrap();
+
+        thread::sleep(Duration::from_millis(50));
+        {
+            let mut file = OpenOptions::new().append(true).open(&file_path).unwrap();
+            write!(file, "No newline").unwrap();
+        }
+
+        let events = collect_until(&rx, |event| {
+            appended(std::slice::from_ref(event), "No newline").is_some()
+        });
+
+        assert_eq!(appended(&events, "No newline"), Some(1));
+    }
+
+    #[test]
+    fn test_number_empty_lines_and_flushed_lines() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("app.log");
+        File::create(&file_path).unwrap();
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        watcher.set_partial_line_timeout(Duration::from_millis(100));
+        let rx = watcher.take_event_receiver().unwrap();
+        watcher.watch_file(file_path.clone()).unwrap();
+
+        thread::sleep(Duration::from_millis(50));
+        let mut file = OpenOptions::new().append(true).open(&file_path).unwrap();
+        write!(file, "Line 1\n\n").unwrap();
+        let events = collect_until(&rx, |event| {
+            appended(std::slice::from_ref(event), "Line 1").is_some()
+        });
+        let lines: Vec<(usize, &str)> = events
+            .iter()
+            .filter_map(|event| match event {
+                FileWatchEvent::ContentAppended {
+                    lines,
+                    first_line_number,
+                    ..
+                } => Some((*first_line_number, lines)),
+                _ => None,
+            })
+            .flat_map(|(first, lines)| (first..).zip(lines.iter().map(String::as_str)))
+            .collect();
+        assert_eq!(lines, [(1, "Line 1"), (2, "")]);
+
+        // A line sent on timeout keeps its number when the rest is written
+        write!(file, "Line 3, ").unwrap();
+        file.flush().unwrap();
+        let events = collect_until(&rx, |event| {
+            appended(std::slice::from_ref(event), "Line 3, ").is_some()
+        });
+        assert_eq!(appended(&events, "Line 3, "), Some(3));
+
+        write!(file, "finished\nLine 4\n").unwrap();
+        let events = collect_until(&rx, |event| {
+            appended(std::slice::from_ref(event), "Line 4").is_some()
+        });
+        assert_eq!(appended(&events, "finished"), Some(3));
+        assert_eq!(appended(&events, "Line 4"), Some(4));
+    }
+
+    #[test]
+    fn test_follow_new_file_in_folder() {
+        let dir = tempdir().unwrap();
+        File::create(dir.path().join("laravel-2024-01-15.log")).unwrap();
+        let new_path = dir.path().join("laravel-2024-01-16.log");
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        let rx = watcher.take_event_receiver().unwrap();
+        watcher
+            .watch_directory(dir.path().to_path_buf(), "laravel-*.log")
+            .unwrap();
+
+        thread::sleep(Duration::from_millis(50));
+        {
+            let mut file = File::create(&new_path).unwrap();
+            writeln!(file, "Line 1").unwrap();
+            writeln!(file, "Line 2").unwrap();
+        }
+        thread::sleep(Duration::from_millis(100));
+        {
+            let mut file = OpenOptions::new().append(true).open(&new_path).unwrap();
+            writeln!(file, "Line 3").unwrap();
+        }
+
+        let events = collect_until(&rx, |event| {
+            appended(std::slice::from_ref(event), "Line 3").is_some()
+        });
+
+        let created = events.iter().position(
+            |event| matches!(event, FileWatchEvent::FileCreated { path } if *path == new_path),
+        );
+        let first_read = events
+            .iter()
+            .position(|event| matches!(event, FileWatchEvent::ContentAppended { .. }));
+        assert!(created.is_some() && created < first_read);
+
+        // Each line is read once and numbered within the new file
+        let reads_of_line_1 = events
+            .iter()
+            .filter(|event| appended(std::slice::from_ref(event), "Line 1").is_some())
+            .count();
+        assert_eq!(reads_of_line_1, 1);
+        assert_eq!(appended(&events, "Line 3"), Some(3));
+        assert!(watcher.is_watching(&new_path));
+        assert!(!watcher.is_watching(&dir.path().join("laravel-2024-01-15.log")));
+    }
+
+    #[test]
+    fn test_follow_only_latest_file_in_folder() {
+        let dir = tempdir().unwrap();
+        let old_path = dir.path().join("laravel-2024-01-14.log");
+        let latest_path = dir.path().join("laravel-2024-01-15.log");
+        File::create(&old_path).unwrap();
+        File::create(&latest_path).unwrap();
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        let rx = watcher.take_event_receiver().unwrap();
+        watcher
+            .watch_directory(dir.path().to_path_buf(), "laravel-*.log")
+            .unwrap();
+        assert!(watcher.is_watching(&latest_path));
+        assert!(!watcher.is_watching(&old_path));
+
+        // Writing to an older file does not switch to it
+        thread::sleep(Duration::from_millis(50));
+        {
+            let mut file = OpenOptions::new().append(true).open(&old_path).unwrap();
+            writeln!(file, "Old line").unwrap();
+        }
+        thread::sleep(Duration::from_millis(100));
+        {
+            let mut file = OpenOptions::new().append(true).open(&latest_path).unwrap();
+            writeln!(file, "Latest line").unwrap();
+        }
+
+        let events = collect_until(&rx, |event| {
+            appended(std::slice::from_ref(event), "Latest line").is_some()
+        });
+        assert_eq!(appended(&events, "Old line"), None);
+        assert!(!watcher.is_watching(&old_path));
+    }
+
+    #[test]
+    fn test_forget_deleted_folder_file() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("app-1.log");
+        {
+            let mut file = File::create(&file_path).unwrap();
+            writeln!(file, "Line 1").unwrap();
+        }
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        let rx = watcher.take_event_receiver().unwrap();
+        watcher
+            .watch_directory(dir.path().to_path_buf(), "app-*.log")
+            .unwrap();
+
+        thread::sleep(Duration::from_millis(50));
+        std::fs::remove_file(&file_path).unwrap();
+        collect_until(
+            &rx,
+            |event| matches!(event, FileWatchEvent::FileDeleted { path } if *path == file_path),
+        );
+        assert!(!watcher.is_watching(&file_path));
+
+        // Created again, it is followed from its start
+        {
+            let mut file = File::create(&file_path).unwrap();
+            writeln!(file, "Line 1 again").unwrap();
+        }
+        let events = collect_until(&rx, |event| {
+            appended(std::slice::from_ref(event), "Line 1 again").is_some()
+        });
+        assert!(events.iter().any(
+            |event| matches!(event, FileWatchEvent::FileRotated { path } if *path == file_path)
+        ));
+        assert_eq!(appended(&events, "Line 1 again"), Some(1));
+        assert!(watcher.is_watching(&file_path));
     }
 
     #[test]
@@ -536,12 +1710,87 @@ mod tests {
         }
 
         let watcher = NotifyFileWatcher::new().unwrap();
-        let lines = watcher.read_initial_content(&file_path, None).unwrap();
+        let lines = watcher
+            .read_initial_content(&file_path, None)
+            .unwrap()
+            .lines;
         assert_eq!(lines.len(), 10);
         assert_eq!(lines[0], (1, "Line 1".to_string()));
         assert_eq!(lines[9], (10, "Line 10".to_string()));
     }
 
+    #[test]
+    fn test_read_initial_content_decodes_lossily() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.log");
+        std::fs::write(&file_path, b"Line 1\nbad \xFF byte\nLine 3\n").unwrap();
+
+        let watcher = NotifyFileWatcher::new().unwrap();
+        let lines = watcher
+            .read_initial_content(&file_path, None)
+            .unwrap()
+            .lines;
+        assert_eq!(lines.len(), 3);
+        assert_eq!(lines[1], (2, "bad \u{FFFD} byte".to_string()));
+        assert_eq!(lines[2], (3, "Line 3".to_string()));
+    }
+
+    #[test]
+    fn test_read_initial_content_in_set_encoding() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.log");
+        std::fs::write(&file_path, b"caf\xE9\n").unwrap();
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        watcher.watch_file(file_path.clone()).unwrap();
+        watcher
+            .set_encoding(&file_path, TextEncoding::Latin1)
+            .unwrap();
+
+        let lines = watcher
+            .read_initial_content(&file_path, None)
+            .unwrap()
+            .lines;
+        assert_eq!(lines, [(1, "café".to_string())]);
+        assert!(watcher
+            .set_encoding(&dir.path().join("other.log"), TextEncoding::Latin1)
+            .is_err());
+    }
+
+    #[test]
+    fn test_follow_utf16_file_with_bom() {
+        fn utf16le(text: &str) -> Vec<u8> {
+            text.encode_utf16().flat_map(u16::to_le_bytes).collect()
+        }
+
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("app.log");
+        let mut content = vec![0xFF, 0xFE];
+        content.extend(utf16le("Line 1\n"));
+        std::fs::write(&file_path, content).unwrap();
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        let rx = watcher.take_event_receiver().unwrap();
+        watcher.watch_file(file_path.clone()).unwrap();
+
+        thread::sleep(Duration::from_millis(50));
+        {
+            let mut file = OpenOptions::new().append(true).open(&file_path).unwrap();
+            file.write_all(&utf16le("Zoë\n")).unwrap();
+        }
+
+        let events = collect_until(&rx, |event| {
+            appended(std::slice::from_ref(event), "Zoë").is_some()
+        });
+        assert_eq!(appended(&events, "Zoë"), Some(2));
+
+        let lines = watcher
+            .read_initial_content(&file_path, None)
+            .unwrap()
+            .lines;
+        assert_eq!(lines, [(1, "Line 1".to_string()), (2, "Zoë".to_string())]);
+    }
+
     #[test]
     fn test_read_initial_content_with_limit() {
         let dir = tempdir().unwrap();
@@ -555,10 +1804,149 @@ mod tests {
         }
 
         let watcher = NotifyFileWatcher::new().unwrap();
-        let lines = watcher.read_initial_content(&file_path, Some(10)).unwrap();
+        let lines = watcher
+            .read_initial_content(&file_path, Some(10))
+            .unwrap()
+            .lines;
         assert_eq!(lines.len(), 10);
         // Should be the last 10 lines
         assert_eq!(lines[0], (91, "Line 91".to_string()));
         assert_eq!(lines[9], (100, "Line 100".to_string()));
     }
+
+    #[test]
+    fn test_read_initial_content_backwards() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.log");
+        {
+            let mut file = File::create(&file_path).unwrap();
+            for i in 1..=20_000 {
+                writeln!(file, "Line {:05}", i).unwrap();
+            }
+        }
+
+        // Not followed: numbered from the counted lines as well
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        let content = watcher.read_initial_content(&file_path, Some(3)).unwrap();
+        assert!(!content.approximate);
+        assert_eq!(content.lines.len(), 3);
+        assert_eq!(content.lines[2], (20_000, "Line 20000".to_string()));
+
+        // Followed: numbered back from the counted lines
+        watcher.watch_file(file_path.clone()).unwrap();
+        let content = watcher
+            .read_initial_content(&file_path, Some(10_000))
+            .unwrap();
+        assert!(!content.approximate);
+        assert_eq!(content.lines.len(), 10_000);
+        assert_eq!(content.lines[0], (10_001, "Line 10001".to_string()));
+        assert_eq!(content.lines[9_999], (20_000, "Line 20000".to_string()));
+    }
+
+    #[test]
+    fn test_follow_large_file_with_estimated_line_numbers() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("huge.log");
+        let count = (EXACT_COUNT_LIMIT / 16 + 1_000) as usize;
+        std::fs::write(&file_path, b"0123456789abcde\n".repeat(count)).unwrap();
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        let rx = watcher.take_event_receiver().unwrap();
+        watcher.watch_file(file_path.clone()).unwrap();
+
+        let content = watcher.read_initial_content(&file_path, Some(2)).unwrap();
+        assert!(content.approximate);
+        assert_eq!(content.lines[1].0, count);
+
+        thread::sleep(Duration::from_millis(50));
+        {
+            let mut file = OpenOptions::new().append(true).open(&file_path).unwrap();
+            writeln!(file, "New line").unwrap();
+        }
+
+        let events = collect_until(&rx, |event| {
+            appended(std::slice::from_ref(event), "New line").is_some()
+        });
+        assert_eq!(appended(&events, "New line"), Some(count + 1));
+        assert!(events.iter().any(|event| matches!(
+            event,
+            FileWatchEvent::ContentAppended {
+                approximate: true,
+                ..
+            }
+        )));
+    }
+
+    #[test]
+    fn test_folder_of_large_files_is_not_read_in_full() {
+        let dir = tempdir().unwrap();
+        let paths: Vec<PathBuf> = (14..=16)
+            .map(|day| dir.path().join(format!("laravel-2024-01-{}.log", day)))
+            .collect();
+        for path in &paths {
+            // Sparse files, so only their last lines take up space
+            let file = File::create(path).unwrap();
+            file.set_len(EXACT_COUNT_LIMIT).unwrap();
+            let mut file = OpenOptions::new().append(true).open(path).unwrap();
+            for i in 1..=5_000 {
+                writeln!(file, "Line {:010}", i).unwrap();
+            }
+        }
+
+        let mut watcher = NotifyFileWatcher::new().unwrap();
+        watcher
+            .watch_directory(dir.path().to_path_buf(), "laravel-*.log")
+            .unwrap();
+        assert!(!watcher.is_watching(&paths[0]));
+        assert!(!watcher.is_watching(&paths[1]));
+        assert!(watcher.is_watching(&paths[2]));
+
+        // Followed or not, the line count is estimated past the limit
+        let mut numbers = Vec::new();
+        for path in &paths {
+            let content = watcher.read_initial_content(path, Some(2)).unwrap();
+            assert!(content.approximate);
+            assert_eq!(content.lines[1].1, "Line 0000005000");
+            numbers.push(content.lines[1].0);
+        }
+        assert!(numbers.iter().all(|number| *number == numbers[0]));
+    }
+
+    #[test]
+    fn test_split_lines_utf16() {
+        // U+010A is 0A 01 in UTF-16LE, and must not end a line
+        let bytes: Vec<u8> = "\u{10A}a\nb"
+            .encode_utf16()
+            .flat_map(u16::to_le_bytes)
+            .collect();
+
+        let lines = split_lines(&bytes, TextEncoding::Utf16Le);
+        assert_eq!(lines.len(), 2);
+        assert_eq!(TextEncoding::Utf16Le.decode_line(lines[0]), "\u{10A}a");
+        assert_eq!(TextEncoding::Utf16Le.decode_line(lines[1]), "b");
+    }
+
+    #[test]
+    fn test_read_sample() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("test.log");
+
+        {
+            let mut file = File::create(&file_path).unwrap();
+            for i in 1..=100 {
+                writeln!(file, "Line {}", i).unwrap();
+            }
+        }
+
+        let watcher = NotifyFileWatcher::new().unwrap();
+        let lines = watcher.read_sample(&file_path, 3).unwrap();
+        assert_eq!(
+            lines,
+            vec!["Line 1", "Line 2", "Line 3", "Line 98", "Line 99", "Line 100"]
+        );
+
+        let lines = watcher.read_sample(&file_path, 60).unwrap();
+        assert_eq!(lines.len(), 100);
+        assert_eq!(lines[60], "Line 61");
+    }
 }
@@ -5,4 +5,4 @@
 
 pub mod file_watcher;
 
-pub use file_watcher::NotifyFileWatcher;
+pub use file_watcher::{InitialContent, NotifyFileWatcher, DEFAULT_PARTIAL_LINE_TIMEOUT};
@@ -0,0 +1,34 @@
+//! Reading and writing JSON files shared by the stores.
+
+use std::path::Path;
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+/// Errors that can occur when reading or writing a stored JSON file.
+#[derive(Debug, thiserror::Error)]
+pub enum StorageError {
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+    #[error("Invalid JSON: {0}")]
+    JsonError(#[from] serde_json::Error),
+}
+
+/// Load a value from a JSON file, returning the default if the file does not exist yet.
+pub(crate) fn load<T: DeserializeOwned + Default>(path: &Path) -> Result<T, StorageError> {
+    match std::fs::read_to_string(path) {
+        Ok(content) => Ok(serde_json::from_str(&content)?),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(T::default()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Save a value as a JSON file, creating the parent directory if needed.
+pub(crate) fn save<T: Serialize>(path: &Path, value: &T) -> Result<(), StorageError> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    let content = serde_json::to_string_pretty(value)?;
+    std::fs::write(path, content)?;
+    Ok(())
+}
@@ -1,3 +1,9 @@
 //! Storage infrastructure for persistence.
 
-// JSON storage will be implemented in Phase 7
+pub mod json_file;
+pub mod parser_store;
+pub mod settings_store;
+
+pub use json_file::StorageError;
+pub use parser_store::{ParserConfig, ParserStore};
+pub use settings_store::{AppSettings, SettingsStore, SourceSettings};
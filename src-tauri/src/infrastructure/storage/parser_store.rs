@@ -0,0 +1,91 @@
+//! JSON file storage for custom parser definitions.
+
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::parsing::CustomParserDefinition;
+
+use super::json_file::{self, StorageError};
+
+/// Persisted parser configuration.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ParserConfig {
+    /// User-defined parser definitions.
+    #[serde(default)]
+    pub custom_parsers: Vec<CustomParserDefinition>,
+    /// Parser name assigned to each source path.
+    #[serde(default)]
+    pub assignments: HashMap<PathBuf, String>,
+    /// User-defined grok patterns, by name.
+    #[serde(default)]
+    pub grok_patterns: BTreeMap<String, String>,
+}
+
+/// Stores parser configuration as a JSON file.
+#[derive(Debug, Clone)]
+pub struct ParserStore {
+    path: PathBuf,
+}
+
+impl ParserStore {
+    /// Create a store backed by the given file.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load the configuration, returning the default if the file does not exist yet.
+    pub fn load(&self) -> Result<ParserConfig, StorageError> {
+        json_file::load(&self.path)
+    }
+
+    /// Save the configuration, creating the parent directory if needed.
+    pub fn save(&self, config: &ParserConfig) -> Result<(), StorageError> {
+        json_file::save(&self.path, config)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::parsing::PatternSyntax;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_load_missing_file_returns_default() {
+        let dir = tempdir().unwrap();
+        let store = ParserStore::new(dir.path().join("parsers.json"));
+
+        assert_eq!(store.load().unwrap(), ParserConfig::default());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempdir().unwrap();
+        let store = ParserStore::new(dir.path().join("nested").join("parsers.json"));
+
+        let mut config = ParserConfig::default();
+        config.custom_parsers.push(CustomParserDefinition {
+            name: "Billing".to_string(),
+            pattern: r"^(?P<message>.*)$".to_string(),
+            syntax: PatternSyntax::Regex,
+            timestamp_format: None,
+            continuation_pattern: None,
+        });
+        config
+            .assignments
+            .insert(PathBuf::from("/var/log/billing.log"), "Billing".to_string());
+        config
+            .grok_patterns
+            .insert("INVOICE".to_string(), r"INV-\d+".to_string());
+
+        store.save(&config).unwrap();
+        assert_eq!(store.load().unwrap(), config);
+    }
+}
@@ -0,0 +1,126 @@
+//! JSON file storage for application and per-source settings.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::log_watching::{LevelAliases, SourceTimezone, TextEncoding};
+
+use super::json_file::{self, StorageError};
+
+/// Settings chosen for one source path.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SourceSettings {
+    /// Timezone naive timestamps are read in.
+    #[serde(default)]
+    pub timezone: SourceTimezone,
+    /// Whether ANSI escapes are left in the text.
+    #[serde(default)]
+    pub raw_ansi: bool,
+    /// The source's own level names and numbers.
+    #[serde(default)]
+    pub level_aliases: LevelAliases,
+    /// Encoding the source's files are decoded in.
+    #[serde(default)]
+    pub encoding: TextEncoding,
+}
+
+impl SourceSettings {
+    /// Checks if nothing differs from the defaults, so there is nothing to store.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// Persisted application settings.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AppSettings {
+    /// Settings for each source path, when any differ from the defaults.
+    #[serde(default)]
+    pub sources: HashMap<PathBuf, SourceSettings>,
+    /// How long a half-written line waits for its newline, when not the default.
+    #[serde(default)]
+    pub partial_line_timeout_ms: Option<u64>,
+}
+
+/// Stores settings as a JSON file.
+#[derive(Debug, Clone)]
+pub struct SettingsStore {
+    path: PathBuf,
+}
+
+impl SettingsStore {
+    /// Create a store backed by the given file.
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// Returns the path of the backing file.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Load the settings, returning the default if the file does not exist yet.
+    pub fn load(&self) -> Result<AppSettings, StorageError> {
+        json_file::load(&self.path)
+    }
+
+    /// Save the settings, creating the parent directory if needed.
+    pub fn save(&self, settings: &AppSettings) -> Result<(), StorageError> {
+        json_file::save(&self.path, settings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::log_watching::LogLevel;
+    use tempfile::tempdir;
+
+    #[test]
+    fn test_load_missing_file_returns_default() {
+        let dir = tempdir().unwrap();
+        let store = SettingsStore::new(dir.path().join("settings.json"));
+
+        assert_eq!(store.load().unwrap(), AppSettings::default());
+    }
+
+    #[test]
+    fn test_save_and_load_round_trip() {
+        let dir = tempdir().unwrap();
+        let store = SettingsStore::new(dir.path().join("nested").join("settings.json"));
+
+        let mut settings = AppSettings::default();
+        settings.sources.insert(
+            PathBuf::from("/var/log/billing.log"),
+            SourceSettings {
+                timezone: "Europe/Oslo".parse().unwrap(),
+                raw_ansi: true,
+                level_aliases: [("FINE", LogLevel::Debug)].into_iter().collect(),
+                encoding: TextEncoding::Windows1252,
+            },
+        );
+        settings.partial_line_timeout_ms = Some(2000);
+
+        store.save(&settings).unwrap();
+        assert_eq!(store.load().unwrap(), settings);
+    }
+
+    #[test]
+    fn test_load_partial_source_settings() {
+        let dir = tempdir().unwrap();
+        let store = SettingsStore::new(dir.path().join("settings.json"));
+        std::fs::write(
+            store.path(),
+            r#"{"sources":{"/var/log/app.log":{"encoding":"latin-1"}}}"#,
+        )
+        .unwrap();
+
+        let settings = store.load().unwrap();
+        let source = &settings.sources[Path::new("/var/log/app.log")];
+        assert_eq!(source.encoding, TextEncoding::Latin1);
+        assert!(!source.raw_ansi);
+        assert_eq!(source.timezone, SourceTimezone::Local);
+    }
+}
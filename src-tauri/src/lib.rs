@@ -13,7 +13,8 @@
 
 use std::sync::{Arc, Mutex};
 
-use log::info;
+use log::{info, warn};
+use tauri::Manager;
 
 // Domain layer
 pub mod domain;
@@ -27,11 +28,16 @@ pub mod infrastructure;
 use application::commands::{
     add_log_file, add_log_folder, clear_all_sources, clear_log_entries, close_log_window,
     create_log_window, detect_laravel_logs, focus_window, focus_window_by_index, get_all_windows,
-    get_laravel_logs, get_latest_laravel_log, get_log_entries, get_log_source, get_log_sources,
-    get_window_for_source, get_window_info, open_in_ide, read_initial_content, remove_log_source,
-    set_window_index, update_source_status, WindowManagerState,
+    get_custom_parsers, get_grok_patterns, get_laravel_logs, get_latest_laravel_log,
+    get_log_entries, get_log_source, get_log_sources, get_parser_names, get_partial_line_timeout,
+    get_window_for_source, get_window_info, open_in_ide, read_initial_content,
+    register_custom_parser, register_grok_pattern, remove_custom_parser, remove_grok_pattern,
+    remove_log_source, set_partial_line_timeout, set_source_encoding, set_source_level_aliases,
+    set_source_parser, set_source_strip_ansi, set_source_timezone, set_window_index,
+    update_source_status, WindowManagerState,
 };
 use application::state::{start_event_processor, LogWatcherState};
+use infrastructure::storage::{ParserStore, SettingsStore};
 
 /// Greet command for testing Tauri IPC
 #[tauri::command]
@@ -68,6 +74,22 @@ pub fn run() {
         .manage(watcher_state.clone())
         .manage(window_state)
         .setup(move |app| {
+            // Load custom parsers and settings from the app config directory
+            match app.path().app_config_dir() {
+                Ok(config_dir) => {
+                    let mut state = watcher_state.lock().unwrap();
+                    let store = ParserStore::new(config_dir.join("parsers.json"));
+                    if let Err(e) = state.load_parser_config(store) {
+                        warn!("{}", e);
+                    }
+                    let store = SettingsStore::new(config_dir.join("settings.json"));
+                    if let Err(e) = state.load_settings(store) {
+                        warn!("{}", e);
+                    }
+                }
+                Err(e) => warn!("Failed to resolve config directory: {}", e),
+            }
+
             // Start the event processor
             start_event_processor(app.handle().clone(), watcher_state.clone());
             Ok(())
@@ -85,6 +107,21 @@ pub fn run() {
             read_initial_content,
             clear_log_entries,
             update_source_status,
+            get_partial_line_timeout,
+            set_partial_line_timeout,
+            // Parser commands
+            register_custom_parser,
+            remove_custom_parser,
+            get_custom_parsers,
+            get_parser_names,
+            set_source_encoding,
+            set_source_level_aliases,
+            set_source_parser,
+            set_source_strip_ansi,
+            set_source_timezone,
+            register_grok_pattern,
+            remove_grok_pattern,
+            get_grok_patterns,
             // Laravel detection commands
             detect_laravel_logs,
             get_latest_laravel_log,
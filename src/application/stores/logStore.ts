@@ -28,8 +28,17 @@ function toLogEntry(entry: BackendLogEntry): LogEntry {
     message: entry.message,
     raw: entry.raw,
     lineNumber: entry.line_number,
+    lineNumberApproximate: entry.line_number_approximate,
     context: entry.context ?? undefined,
     stackTrace: entry.stack_trace ?? undefined,
+    stackFrames: entry.stack_frames?.map(frame => ({
+      file: frame.file,
+      line: frame.line,
+      function: frame.function,
+      class: frame.class,
+      isVendor: frame.is_vendor,
+    })),
+    styles: entry.styles,
   });
 }
 
@@ -113,7 +122,25 @@ export const useLogStore = defineStore('log', () => {
         triggerRef(entries);
       });
 
-      unlisteners.value = [logEntriesUnlisten, sourceStatusUnlisten, fileTruncatedUnlisten];
+      // A folder source moved on to a newer file; its entries start over
+      const sourceSwitchedFileUnlisten = await LogApi.onSourceSwitchedFile(event => {
+        entries.value.set(event.source_id, []);
+        triggerRef(entries);
+      });
+
+      // A followed file was replaced by a new one; line numbers restart
+      const fileRotatedUnlisten = await LogApi.onFileRotated(event => {
+        entries.value.set(event.source_id, []);
+        triggerRef(entries);
+      });
+
+      unlisteners.value = [
+        logEntriesUnlisten,
+        sourceStatusUnlisten,
+        fileTruncatedUnlisten,
+        sourceSwitchedFileUnlisten,
+        fileRotatedUnlisten,
+      ];
 
       // Load existing sources from backend
       const backendSources = await LogApi.getLogSources();
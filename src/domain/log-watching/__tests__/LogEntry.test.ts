@@ -182,6 +182,28 @@ describe('LogEntry', () => {
     });
   });
 
+  describe('formattedLineNumber', () => {
+    it('should show the line number', () => {
+      const entry = LogEntry.fromRaw('Test', 42);
+      expect(entry.lineNumberApproximate).toBe(false);
+      expect(entry.formattedLineNumber).toBe('42');
+    });
+
+    it('should mark an estimated line number', () => {
+      const entry = LogEntry.create({
+        id: 'test-1',
+        timestamp: null,
+        level: LogLevel.from('info'),
+        message: 'Test',
+        raw: 'Test',
+        lineNumber: 1250000,
+        lineNumberApproximate: true,
+      });
+
+      expect(entry.formattedLineNumber).toBe('~1250000');
+    });
+  });
+
   describe('immutability', () => {
     it('should be frozen', () => {
       const entry = LogEntry.fromRaw('Test', 1);
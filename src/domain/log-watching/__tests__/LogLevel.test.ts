@@ -49,6 +49,7 @@ describe('LogLevel', () => {
       expect(LogLevel.fromString('err').value).toBe('error');
       expect(LogLevel.fromString('fatal').value).toBe('critical');
       expect(LogLevel.fromString('emergency').value).toBe('critical');
+      expect(LogLevel.fromString('trace').value).toBe('debug');
     });
 
     it('should trim whitespace from input', () => {
@@ -1,5 +1,34 @@
 import { LogLevel } from '../value-objects/LogLevel';
 
+/**
+ * A structured stack trace frame.
+ */
+export interface StackFrame {
+  file: string | null;
+  line: number | null;
+  function: string | null;
+  class: string | null;
+  isVendor: boolean;
+}
+
+/**
+ * A styled range of the raw line, in character offsets.
+ */
+export interface StyleSpan {
+  start: number;
+  end: number;
+  style: {
+    /** Palette index (0-255) or `[r, g, b]`. */
+    foreground: number | [number, number, number] | null;
+    background: number | [number, number, number] | null;
+    bold: boolean;
+    dim: boolean;
+    italic: boolean;
+    underline: boolean;
+    inverse: boolean;
+  };
+}
+
 /**
  * Represents a parsed log entry from a log file.
  */
@@ -10,8 +39,11 @@ export interface LogEntryProps {
   message: string;
   raw: string;
   lineNumber: number;
+  lineNumberApproximate?: boolean;
   context?: Record<string, unknown>;
   stackTrace?: string[];
+  stackFrames?: StackFrame[];
+  styles?: StyleSpan[];
 }
 
 /**
@@ -25,8 +57,11 @@ export class LogEntry {
   public readonly message: string;
   public readonly raw: string;
   public readonly lineNumber: number;
+  public readonly lineNumberApproximate: boolean;
   public readonly context: Record<string, unknown>;
   public readonly stackTrace: string[];
+  public readonly stackFrames: StackFrame[];
+  public readonly styles: StyleSpan[];
 
   private constructor(props: LogEntryProps) {
     this.id = props.id;
@@ -35,8 +70,11 @@ export class LogEntry {
     this.message = props.message;
     this.raw = props.raw;
     this.lineNumber = props.lineNumber;
+    this.lineNumberApproximate = props.lineNumberApproximate ?? false;
     this.context = props.context ?? {};
     this.stackTrace = props.stackTrace ?? [];
+    this.stackFrames = props.stackFrames ?? [];
+    this.styles = props.styles ?? [];
     Object.freeze(this);
   }
 
@@ -91,6 +129,14 @@ export class LogEntry {
     return this.message.match(urlRegex) ?? [];
   }
 
+  /**
+   * Gets the line number for display, marked with "~" when it is estimated.
+   * @returns Formatted line number
+   */
+  get formattedLineNumber(): string {
+    return this.lineNumberApproximate ? `~${this.lineNumber}` : `${this.lineNumber}`;
+  }
+
   /**
    * Gets a display-friendly timestamp string.
    * @returns Formatted timestamp or empty string if none
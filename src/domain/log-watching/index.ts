@@ -1,6 +1,6 @@
 // Entities
 export { LogEntry } from './entities/LogEntry';
-export type { LogEntryProps } from './entities/LogEntry';
+export type { LogEntryProps, StackFrame, StyleSpan } from './entities/LogEntry';
 export { LogSource } from './entities/LogSource';
 export type { LogSourceType, LogSourceStatus, LogSourceProps } from './entities/LogSource';
 
@@ -13,6 +13,7 @@ const SEVERITY_MAP: Record<LogLevelType, number> = {
 };
 
 const LEVEL_ALIASES: Record<string, LogLevelType> = {
+  trace: 'debug',
   debug: 'debug',
   info: 'info',
   information: 'info',
@@ -26,6 +26,32 @@ export interface BackendLogSource {
   error_message: string | null;
   created_at: string;
   last_activity_at: string | null;
+  parser: string | null;
+  parser_detected: boolean;
+  /** IANA name, fixed offset such as "+02:00", or "local". */
+  timezone: string;
+  strip_ansi: boolean;
+  /** The source's own level names and numbers, lower-cased, mapped to levels. */
+  level_aliases: Record<string, string>;
+  /** "utf-8", "latin-1", "windows-1252", "utf-16le" or "utf-16be". */
+  encoding: string;
+  current_file: string | null;
+}
+
+/**
+ * Syntax of a custom parser pattern.
+ */
+export type PatternSyntax = 'regex' | 'grok';
+
+/**
+ * User-defined regex or grok parser from the backend.
+ */
+export interface CustomParserDefinition {
+  name: string;
+  pattern: string;
+  syntax: PatternSyntax;
+  timestamp_format: string | null;
+  continuation_pattern: string | null;
 }
 
 /**
@@ -45,6 +71,52 @@ export interface GetEntriesResponse {
   total_count: number;
 }
 
+/**
+ * Stack trace frame from the backend.
+ */
+export interface BackendStackFrame {
+  file: string | null;
+  line: number | null;
+  function: string | null;
+  class: string | null;
+  is_vendor: boolean;
+}
+
+/**
+ * Structured exception from the backend.
+ */
+export interface BackendExceptionInfo {
+  class: string;
+  code: string | null;
+  message: string;
+  file: string | null;
+  line: number | null;
+  frames: BackendStackFrame[];
+  previous: BackendExceptionInfo | null;
+}
+
+/**
+ * Terminal colour from the backend: a palette index or `[r, g, b]`.
+ */
+export type BackendAnsiColor = number | [number, number, number];
+
+/**
+ * Styled range of a log line, from stripped ANSI escapes.
+ */
+export interface BackendStyleSpan {
+  start: number;
+  end: number;
+  style: {
+    foreground: BackendAnsiColor | null;
+    background: BackendAnsiColor | null;
+    bold: boolean;
+    dim: boolean;
+    italic: boolean;
+    underline: boolean;
+    inverse: boolean;
+  };
+}
+
 /**
  * Log entry from the backend.
  */
@@ -55,9 +127,15 @@ export interface BackendLogEntry {
   message: string;
   raw: string;
   line_number: number;
+  /** Whether the line number is estimated, near the end of a large file. */
+  line_number_approximate: boolean;
   context: Record<string, unknown> | null;
+  extra: Record<string, unknown> | null;
   stack_trace: string[] | null;
+  stack_frames: BackendStackFrame[];
+  exception: BackendExceptionInfo | null;
   channel: string | null;
+  styles: BackendStyleSpan[];
 }
 
 /**
@@ -98,6 +176,21 @@ export interface FileTruncatedEvent {
   source_id: string;
 }
 
+/**
+ * Event payload for file rotated.
+ */
+export interface FileRotatedEvent {
+  source_id: string;
+}
+
+/**
+ * Event payload for a folder source moving on to a newer file.
+ */
+export interface SourceSwitchedFileEvent {
+  source_id: string;
+  path: string;
+}
+
 /**
  * Event names matching the backend.
  */
@@ -107,6 +200,8 @@ export const EventNames = {
   SOURCE_ADDED: 'source-added',
   SOURCE_REMOVED: 'source-removed',
   FILE_TRUNCATED: 'file-truncated',
+  FILE_ROTATED: 'file-rotated',
+  SOURCE_SWITCHED_FILE: 'source-switched-file',
 } as const;
 
 /**
@@ -183,6 +278,120 @@ export const LogApi = {
     return invoke<void>('update_source_status', { sourceId, status });
   },
 
+  /**
+   * Get how long a half-written line waits for its newline, in milliseconds.
+   */
+  async getPartialLineTimeout(): Promise<number> {
+    return invoke<number>('get_partial_line_timeout');
+  },
+
+  /**
+   * Set how long a half-written line waits for its newline, in milliseconds.
+   */
+  async setPartialLineTimeout(timeoutMs: number): Promise<void> {
+    return invoke<void>('set_partial_line_timeout', { timeoutMs });
+  },
+
+  /**
+   * Register or replace a user-defined regex or grok parser.
+   */
+  async registerCustomParser(
+    name: string,
+    pattern: string,
+    syntax?: PatternSyntax,
+    timestampFormat?: string,
+    continuationPattern?: string
+  ): Promise<void> {
+    return invoke<void>('register_custom_parser', {
+      name,
+      pattern,
+      syntax,
+      timestampFormat,
+      continuationPattern,
+    });
+  },
+
+  /**
+   * Remove a user-defined regex parser.
+   */
+  async removeCustomParser(name: string): Promise<void> {
+    return invoke<void>('remove_custom_parser', { name });
+  },
+
+  /**
+   * Get all user-defined regex parsers.
+   */
+  async getCustomParsers(): Promise<CustomParserDefinition[]> {
+    return invoke<CustomParserDefinition[]>('get_custom_parsers');
+  },
+
+  /**
+   * Register or replace a user-defined grok pattern.
+   */
+  async registerGrokPattern(name: string, definition: string): Promise<void> {
+    return invoke<void>('register_grok_pattern', { name, definition });
+  },
+
+  /**
+   * Remove a user-defined grok pattern.
+   */
+  async removeGrokPattern(name: string): Promise<void> {
+    return invoke<void>('remove_grok_pattern', { name });
+  },
+
+  /**
+   * Get all user-defined grok patterns, by name.
+   */
+  async getGrokPatterns(): Promise<Record<string, string>> {
+    return invoke<Record<string, string>>('get_grok_patterns');
+  },
+
+  /**
+   * Get the names of all built-in and custom parsers.
+   */
+  async getParserNames(): Promise<string[]> {
+    return invoke<string[]>('get_parser_names');
+  },
+
+  /**
+   * Assign a parser to a source, overriding format detection (null re-detects).
+   */
+  async setSourceParser(sourceId: string, parser: string | null): Promise<BackendLogSource> {
+    return invoke<BackendLogSource>('set_source_parser', { sourceId, parser });
+  },
+
+  /**
+   * Set the timezone a source writes naive timestamps in and re-parse its
+   * buffered entries.
+   */
+  async setSourceTimezone(sourceId: string, timezone: string): Promise<BackendLogSource> {
+    return invoke<BackendLogSource>('set_source_timezone', { sourceId, timezone });
+  },
+
+  /**
+   * Set whether ANSI escapes are stripped from a source's lines.
+   */
+  async setSourceStripAnsi(sourceId: string, stripAnsi: boolean): Promise<BackendLogSource> {
+    return invoke<BackendLogSource>('set_source_strip_ansi', { sourceId, stripAnsi });
+  },
+
+  /**
+   * Set the encoding a source's files are decoded in. Buffered entries are dropped.
+   */
+  async setSourceEncoding(sourceId: string, encoding: string): Promise<BackendLogSource> {
+    return invoke<BackendLogSource>('set_source_encoding', { sourceId, encoding });
+  },
+
+  /**
+   * Set the level names and numbers a source uses, replacing its aliases.
+   */
+  async setSourceLevelAliases(
+    sourceId: string,
+    aliases: Record<string, string>
+  ): Promise<BackendLogSource> {
+    return invoke<BackendLogSource>('set_source_level_aliases', { sourceId, aliases });
+  },
+
   /**
    * Check if a directory contains Laravel daily logs.
    */
@@ -237,6 +446,26 @@ export const LogApi = {
       callback(event.payload);
     });
   },
+
+  /**
+   * Subscribe to file rotated events.
+   */
+  async onFileRotated(callback: (event: FileRotatedEvent) => void): Promise<UnlistenFn> {
+    return listen<FileRotatedEvent>(EventNames.FILE_ROTATED, event => {
+      callback(event.payload);
+    });
+  },
+
+  /**
+   * Subscribe to folder sources switching to a newer file.
+   */
+  async onSourceSwitchedFile(
+    callback: (event: SourceSwitchedFileEvent) => void
+  ): Promise<UnlistenFn> {
+    return listen<SourceSwitchedFileEvent>(EventNames.SOURCE_SWITCHED_FILE, event => {
+      callback(event.payload);
+    });
+  },
 };
 
 export default LogApi;